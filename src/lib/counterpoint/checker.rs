@@ -0,0 +1,497 @@
+use super::super::{BeatDuration, BeatTime, ChromaticRange, Note, CHROMATIC_COUNT, QN};
+use super::{CounterpointChecker, CounterpointRule, CounterpointViolation, Line, Species};
+
+impl CounterpointRule {
+    pub const ALL: [CounterpointRule; 10] = [
+        CounterpointRule::DissonantStrongBeat,
+        CounterpointRule::DissonantWeakBeat,
+        CounterpointRule::ParallelPerfects,
+        CounterpointRule::UnpreparedSuspension,
+        CounterpointRule::UnresolvedSuspension,
+        CounterpointRule::UnrecoveredLeap,
+        CounterpointRule::DissonantLeap,
+        CounterpointRule::ImperfectBoundary,
+        CounterpointRule::OutOfRange,
+        CounterpointRule::WrongRhythm,
+    ];
+}
+
+impl std::fmt::Display for CounterpointRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(match self {
+            CounterpointRule::DissonantStrongBeat => "dissonance on a strong beat",
+            CounterpointRule::DissonantWeakBeat => "weak beat dissonance that is not passing",
+            CounterpointRule::ParallelPerfects => "parallel perfect consonances",
+            CounterpointRule::UnpreparedSuspension => "unprepared suspension",
+            CounterpointRule::UnresolvedSuspension => "suspension not resolving down by step",
+            CounterpointRule::UnrecoveredLeap => "leap not recovered by step",
+            CounterpointRule::DissonantLeap => "dissonant leap",
+            CounterpointRule::ImperfectBoundary => {
+                "not beginning or ending on a perfect consonance"
+            }
+            CounterpointRule::OutOfRange => "note out of range",
+            CounterpointRule::WrongRhythm => "note value foreign to the species",
+        })
+    }
+}
+
+impl CounterpointViolation {
+    pub fn new(rule: CounterpointRule, position: BeatTime, lines: &[usize]) -> Self {
+        CounterpointViolation {
+            rule,
+            position,
+            lines: lines.to_vec(),
+        }
+    }
+
+    pub fn rule(&self) -> CounterpointRule {
+        self.rule
+    }
+
+    pub fn position(&self) -> BeatTime {
+        self.position
+    }
+
+    /// Indices of the lines involved.
+    pub fn lines(&self) -> &[usize] {
+        &self.lines
+    }
+}
+
+/// Prints e.g. `parallel perfect consonances (lines 0, 1) at beat 8`, counting quarter notes from zero.
+impl std::fmt::Display for CounterpointViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.rule)?;
+        for (i, line) in self.lines.iter().enumerate() {
+            f.write_str(if i == 0 { " (lines " } else { ", " })?;
+            write!(f, "{}", line)?;
+        }
+        if !self.lines.is_empty() {
+            f.write_str(")")?;
+        }
+        write!(f, " at beat {}", f64::from(self.position) / f64::from(QN))
+    }
+}
+
+impl CounterpointChecker {
+    pub fn new(species: Species, bar: BeatDuration) -> CounterpointChecker {
+        CounterpointChecker {
+            species,
+            bar,
+            ranges: Vec::new(),
+        }
+    }
+
+    /// Also checks each line against the range at the same index.
+    pub fn with_ranges(&self, ranges: &[ChromaticRange]) -> CounterpointChecker {
+        CounterpointChecker {
+            ranges: ranges.to_vec(),
+            ..self.clone()
+        }
+    }
+
+    pub fn species(&self) -> Species {
+        self.species
+    }
+
+    pub fn bar(&self) -> BeatDuration {
+        self.bar
+    }
+
+    /// All violations in `lines`, ordered by position. `lines[0]` is the cantus firmus.
+    pub fn check(&self, lines: &[Line]) -> Vec<CounterpointViolation> {
+        let mut violations = Vec::new();
+        for (ix, line) in lines.iter().enumerate() {
+            self.check_melody(ix, line, &mut violations);
+        }
+
+        let mut times: Vec<BeatTime> = lines
+            .iter()
+            .flat_map(|line| line.onsets().iter().copied())
+            .collect();
+        times.sort();
+        times.dedup();
+
+        // Second and third species also forbid perfects on consecutive downbeats.
+        let checks_downbeats = [Species::Second, Species::Third].contains(&self.species);
+        let mut previous_strong = None;
+        for (time_ix, &time) in times.iter().enumerate() {
+            let is_strong = time % self.bar == BeatDuration::default();
+            for a in 0..lines.len() {
+                for b in a + 1..lines.len() {
+                    self.check_pair(lines, (a, b), time, is_strong, &mut violations);
+                    if time_ix > 0 {
+                        let previous = times[time_ix - 1];
+                        self.check_parallels(lines, (a, b), previous, time, &mut violations);
+                    }
+                    if let (true, true, Some(previous)) =
+                        (checks_downbeats, is_strong, previous_strong)
+                    {
+                        self.check_parallels(lines, (a, b), previous, time, &mut violations);
+                    }
+                }
+            }
+            if is_strong {
+                previous_strong = Some(time);
+            }
+        }
+
+        for b in 1..lines.len() {
+            let shared: Vec<BeatTime> = times
+                .iter()
+                .copied()
+                .filter(|&t| lines[0].sounding_at(t).is_some() && lines[b].sounding_at(t).is_some())
+                .collect();
+            for &time in shared.first().iter().chain(shared.last().iter()) {
+                let (lower, upper) = (note_at(&lines[0], *time), note_at(&lines[b], *time));
+                if ![0, 7].contains(&interval_class(lower, upper)) {
+                    violations.push(CounterpointViolation::new(
+                        CounterpointRule::ImperfectBoundary,
+                        *time,
+                        &[0, b],
+                    ));
+                }
+            }
+        }
+
+        let mut seen = std::collections::HashSet::new();
+        violations.retain(|v| seen.insert(v.clone()));
+        violations.sort_by_key(|v| v.position);
+        violations
+    }
+
+    fn check_melody(&self, ix: usize, line: &Line, violations: &mut Vec<CounterpointViolation>) {
+        let notes = line.notes();
+        let onsets = line.onsets();
+        if let Some(range) = self.ranges.get(ix) {
+            for (&note, &onset) in notes.iter().zip(onsets) {
+                if !range.contains(note) {
+                    violations.push(CounterpointViolation::new(
+                        CounterpointRule::OutOfRange,
+                        onset,
+                        &[ix],
+                    ));
+                }
+            }
+        }
+
+        if ix > 0 {
+            let allowed: &[BeatDuration] = match self.species {
+                Species::First => &[self.bar],
+                Species::Second | Species::Fourth => &[self.bar / 2, self.bar],
+                Species::Third => &[self.bar / 4, self.bar],
+                Species::Fifth => &[],
+            };
+            for (&duration, &onset) in line.durations().iter().zip(onsets) {
+                if !allowed.is_empty() && !allowed.contains(&duration) {
+                    violations.push(CounterpointViolation::new(
+                        CounterpointRule::WrongRhythm,
+                        onset,
+                        &[ix],
+                    ));
+                }
+            }
+        }
+
+        for i in 1..notes.len() {
+            let leap = (notes[i] - notes[i - 1]).semitones();
+            if [6, 10, 11].contains(&leap.abs()) || leap.abs() > CHROMATIC_COUNT as i8 {
+                violations.push(CounterpointViolation::new(
+                    CounterpointRule::DissonantLeap,
+                    onsets[i],
+                    &[ix],
+                ));
+            }
+            if leap.abs() > 4 && i + 1 < notes.len() {
+                let recovery = (notes[i + 1] - notes[i]).semitones();
+                if !is_step(recovery) || recovery.signum() == leap.signum() {
+                    violations.push(CounterpointViolation::new(
+                        CounterpointRule::UnrecoveredLeap,
+                        onsets[i + 1],
+                        &[ix],
+                    ));
+                }
+            }
+        }
+    }
+
+    fn check_pair(
+        &self,
+        lines: &[Line],
+        (a, b): (usize, usize),
+        time: BeatTime,
+        is_strong: bool,
+        violations: &mut Vec<CounterpointViolation>,
+    ) {
+        let (note_a, note_b) = match (lines[a].sounding_at(time), lines[b].sounding_at(time)) {
+            (Some(x), Some(y)) => (lines[a].notes()[x], lines[b].notes()[y]),
+            _ => return,
+        };
+        let lowest = lowest_at(lines, time);
+        if is_consonant(note_a, note_b, lowest) {
+            return;
+        }
+
+        let attacks = |ix: usize| lines[ix].onsets().contains(&time);
+        let held = match (attacks(a), attacks(b)) {
+            (true, false) => Some(b),
+            (false, true) => Some(a),
+            _ => None,
+        };
+        if is_strong {
+            let allows_suspensions = [Species::Fourth, Species::Fifth].contains(&self.species);
+            match held {
+                Some(held) if allows_suspensions && held != 0 => {
+                    let other = if held == a { b } else { a };
+                    let line = &lines[held];
+                    let k = line.sounding_at(time).unwrap();
+                    let onset = line.onsets()[k];
+                    let prepared = lines[other]
+                        .sounding_at(onset)
+                        .map(|o| lines[other].notes()[o])
+                        .is_some_and(|o| is_consonant(o, line.notes()[k], lowest_at(lines, onset)));
+                    if !prepared {
+                        violations.push(CounterpointViolation::new(
+                            CounterpointRule::UnpreparedSuspension,
+                            time,
+                            &[a, b],
+                        ));
+                    }
+                    let resolved = k + 1 < line.len() && {
+                        let step = (line.notes()[k + 1] - line.notes()[k]).semitones();
+                        let resolution = line.onsets()[k + 1];
+                        let other_note = lines[other].sounding_at(resolution);
+                        (step == -1 || step == -2)
+                            && other_note.is_none_or(|o| {
+                                let o = lines[other].notes()[o];
+                                is_consonant(o, line.notes()[k + 1], lowest_at(lines, resolution))
+                            })
+                    };
+                    if !resolved {
+                        violations.push(CounterpointViolation::new(
+                            CounterpointRule::UnresolvedSuspension,
+                            time,
+                            &[a, b],
+                        ));
+                    }
+                }
+                _ => violations.push(CounterpointViolation::new(
+                    CounterpointRule::DissonantStrongBeat,
+                    time,
+                    &[a, b],
+                )),
+            }
+        } else {
+            let moving = match held {
+                Some(held) => Some(if held == a { b } else { a }),
+                None if attacks(b) => Some(b),
+                None => None,
+            };
+            let moving = match moving {
+                Some(moving) => moving,
+                None => return,
+            };
+            let line = &lines[moving];
+            let k = line.sounding_at(time).unwrap();
+            let allows_neighbours = [Species::Third, Species::Fifth].contains(&self.species);
+            let is_passing = k > 0 && k + 1 < line.len() && {
+                let into = (line.notes()[k] - line.notes()[k - 1]).semitones();
+                let out = (line.notes()[k + 1] - line.notes()[k]).semitones();
+                is_step(into)
+                    && is_step(out)
+                    && (into.signum() == out.signum() || allows_neighbours)
+            };
+            if self.species == Species::First || !is_passing {
+                violations.push(CounterpointViolation::new(
+                    CounterpointRule::DissonantWeakBeat,
+                    time,
+                    &[a, b],
+                ));
+            }
+        }
+    }
+
+    fn check_parallels(
+        &self,
+        lines: &[Line],
+        (a, b): (usize, usize),
+        from: BeatTime,
+        to: BeatTime,
+        violations: &mut Vec<CounterpointViolation>,
+    ) {
+        let notes = |time| match (lines[a].sounding_at(time), lines[b].sounding_at(time)) {
+            (Some(x), Some(y)) => Some((lines[a].notes()[x], lines[b].notes()[y])),
+            _ => None,
+        };
+        if let (Some((a0, b0)), Some((a1, b1))) = (notes(from), notes(to)) {
+            let before = interval_class(a0, b0);
+            let after = interval_class(a1, b1);
+            if a0 != a1 && b0 != b1 && before == after && (after == 0 || after == 7) {
+                violations.push(CounterpointViolation::new(
+                    CounterpointRule::ParallelPerfects,
+                    to,
+                    &[a, b],
+                ));
+            }
+        }
+    }
+}
+
+fn note_at(line: &Line, time: BeatTime) -> Note {
+    line.notes()[line.sounding_at(time).unwrap()]
+}
+
+fn lowest_at(lines: &[Line], time: BeatTime) -> Option<Note> {
+    lines
+        .iter()
+        .filter_map(|line| line.sounding_at(time).map(|ix| line.notes()[ix]))
+        .min()
+}
+
+fn interval_class(a: Note, b: Note) -> i8 {
+    (a - b).semitones().abs() % CHROMATIC_COUNT as i8
+}
+
+fn is_step(semitones: i8) -> bool {
+    semitones.abs() == 1 || semitones.abs() == 2
+}
+
+/// Unisons, thirds, fifths, sixths and octaves, with fourths only consonant above a voice that is
+/// not the lowest one sounding.
+fn is_consonant(a: Note, b: Note, lowest: Option<Note>) -> bool {
+    match interval_class(a, b) {
+        0 | 3 | 4 | 7 | 8 | 9 => true,
+        5 => Some(a.min(b)) != lowest,
+        _ => false,
+    }
+}
+
+/******************************************************************************
+* TESTS
+******************************************************************************/
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::{QN, WN};
+    use super::*;
+
+    /// Notes written as e.g. `A4:4 B4:2 C5`, each lasting as many quarter notes as given after the
+    /// colon, or a whole note, starting `rest` quarter notes in.
+    fn line(rest: u64, notes: &str) -> Line {
+        let mut line = Line::new();
+        let mut onset = BeatTime::zero() + QN * rest;
+        for token in notes.split_whitespace() {
+            let mut parts = token.split(':');
+            let note: Note = parts.next().unwrap().parse().unwrap();
+            let duration = parts.next().map_or(WN, |q| QN * q.parse::<u64>().unwrap());
+            line.push(note, onset, duration);
+            onset += duration;
+        }
+        line
+    }
+
+    /// The rules broken by `lines` in `species`, each with its position in quarter notes and the
+    /// lines involved.
+    fn check(species: Species, lines: &[Line]) -> Vec<(CounterpointRule, f64, Vec<usize>)> {
+        CounterpointChecker::new(species, WN)
+            .check(lines)
+            .iter()
+            .map(|v| {
+                (
+                    v.rule(),
+                    f64::from(v.position()) / f64::from(QN),
+                    v.lines().to_vec(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn textbook_exercises() {
+        let fux = line(0, "D4 F4 E4 D4 G4 F4 A4 G4 F4 E4 D4");
+        let first = line(0, "A4 A4 G4 A4 B4 C5 C5 B4 D5 C#5 D5");
+        assert_eq!(check(Species::First, &[fux, first]), []);
+
+        let cantus = line(0, "C4 E4 D4 C4");
+        let second = line(0, "G4:2 E4:2 G4:2 A4:2 B4:2 A4:2 C5");
+        assert_eq!(check(Species::Second, &[cantus, second]), []);
+
+        let cantus = line(0, "C4 D4 C4");
+        let third = line(0, "G4:1 A4:1 B4:1 C5:1 F4:1 G4:1 A4:1 B4:1 C5");
+        assert_eq!(check(Species::Third, &[cantus, third]), []);
+
+        let cantus = line(0, "D4 F4 E4 D4");
+        let fourth = line(2, "A4 D5 C#5:2 D5");
+        assert_eq!(check(Species::Fourth, &[cantus.clone(), fourth]), []);
+
+        let fifth = line(0, "A4:2 B4:1 C5:1 D5:6 C#5:2 D5");
+        assert_eq!(check(Species::Fifth, &[cantus, fifth]), []);
+    }
+
+    #[test]
+    fn one_violation_of_each_rule() {
+        use CounterpointRule::*;
+        let cantus = line(0, "D4 F4 E4 D4");
+        let first_species = [
+            ("A4 G4 B4 D5", DissonantStrongBeat, 4., vec![0, 1]),
+            ("D5 D5 G#4 A4", DissonantLeap, 8., vec![1]),
+            ("F4 A4 B4 D5", ImperfectBoundary, 0., vec![0, 1]),
+            ("A4 A4 B4 D5:2", WrongRhythm, 12., vec![1]),
+        ];
+        for (counterpoint, rule, beat, lines) in first_species.iter() {
+            assert_eq!(
+                check(Species::First, &[cantus.clone(), line(0, counterpoint)]),
+                [(*rule, *beat, lines.clone())],
+                "{}",
+                rule
+            );
+        }
+        assert_eq!(
+            check(Species::Fourth, &[cantus.clone(), line(2, "D5 D5 B4:2 D5")]),
+            [(UnresolvedSuspension, 8., vec![0, 1])]
+        );
+
+        let second = line(0, "G4:2 F4:2 G4:2 A4:2 B4:2 A4:2 C5");
+        assert_eq!(
+            check(Species::Second, &[line(0, "C4 E4 D4 C4"), second]),
+            [(DissonantWeakBeat, 2., vec![0, 1])]
+        );
+        let fifths = line(0, "A4 A4 B4 A4 G4");
+        assert_eq!(
+            check(Species::First, &[line(0, "D4 F4 E4 D4 G4"), fifths]),
+            [(ParallelPerfects, 12., vec![0, 1])]
+        );
+        assert_eq!(
+            check(
+                Species::First,
+                &[line(0, "D4 A4 B4 D4"), line(0, "D5 C#5 D5 D5")]
+            ),
+            [(UnrecoveredLeap, 8., vec![0])]
+        );
+        // Held over the bar line from a dissonance, which is itself not passing.
+        let unprepared = line(2, "D5 F#5 E5:2 A4");
+        assert_eq!(
+            check(Species::Fourth, &[cantus.clone(), unprepared]),
+            [
+                (DissonantWeakBeat, 6., vec![0, 1]),
+                (UnpreparedSuspension, 8., vec![0, 1]),
+            ]
+        );
+
+        let alto = ChromaticRange::new("D4".parse().unwrap(), "C5".parse().unwrap());
+        let checker = CounterpointChecker::new(Species::First, WN).with_ranges(&[alto, alto]);
+        let violations = checker.check(&[cantus, line(0, "A4 A4 B4 D5")]);
+        assert_eq!(
+            violations,
+            [CounterpointViolation::new(
+                OutOfRange,
+                BeatTime::zero() + WN * 3,
+                &[1]
+            )]
+        );
+        assert_eq!(
+            violations[0].to_string(),
+            "note out of range (lines 1) at beat 12"
+        );
+    }
+}
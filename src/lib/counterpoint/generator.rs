@@ -0,0 +1,206 @@
+use rand::Rng;
+
+use super::super::interval_constants::{MIN_2, PER_5};
+use super::super::{BeatDuration, BeatTime, ChromaticRange, Key, Note};
+use super::{CounterpointChecker, CounterpointGenerator, CounterpointRule, Line, Species};
+
+const DEFAULT_BEAM_WIDTH: usize = 24;
+const VIOLATION_COST: f64 = 100.;
+const PENDING_COST: f64 = 5.;
+
+struct Candidate {
+    line: Line,
+    melodic_cost: f64,
+    cost: f64,
+}
+
+impl CounterpointGenerator {
+    pub fn new(
+        species: Species,
+        bar: BeatDuration,
+        range: ChromaticRange,
+    ) -> CounterpointGenerator {
+        CounterpointGenerator {
+            checker: CounterpointChecker::new(species, bar),
+            range,
+            beam_width: DEFAULT_BEAM_WIDTH,
+        }
+    }
+
+    /// How many partial lines to keep after each note. Wider beams find valid lines more often but
+    /// take longer.
+    pub fn with_beam_width(&self, beam_width: usize) -> CounterpointGenerator {
+        assert!(beam_width > 0);
+        CounterpointGenerator {
+            beam_width,
+            ..self.clone()
+        }
+    }
+
+    pub fn checker(&self) -> &CounterpointChecker {
+        &self.checker
+    }
+
+    pub fn range(&self) -> ChromaticRange {
+        self.range
+    }
+
+    /// A line in `key` against `cantus`, one cantus note per bar, breaking no rule of the checker.
+    /// `None` if the search found no such line.
+    pub fn generate<R: Rng>(&self, cantus: &[Note], key: &Key, rng: &mut R) -> Option<Line> {
+        let cantus_final = *cantus.last()?;
+        let bar = self.checker.bar();
+        let cantus = Line::even(cantus, bar);
+        let slots = self.rhythm(cantus.len(), rng);
+
+        let mut beam = vec![Candidate {
+            line: Line::new(),
+            melodic_cost: 0.,
+            cost: 0.,
+        }];
+        for (slot_ix, &(onset, duration)) in slots.iter().enumerate() {
+            let is_last = slot_ix + 1 == slots.len();
+            let notes =
+                self.candidate_notes(key, cantus_final, slot_ix + 2 == slots.len(), is_last);
+            let heard = cantus.onsets().iter().filter(|&&t| t <= onset).count();
+            let cantus_prefix = Line::even(&cantus.notes()[..heard], bar);
+
+            let mut next = Vec::new();
+            for candidate in beam.iter() {
+                for &note in notes.iter() {
+                    let mut line = candidate.line.clone();
+                    line.push(note, onset, duration);
+                    // Violations the next notes may still fix only count for a little.
+                    let violation_cost: f64 = self
+                        .checker
+                        .check(&[cantus_prefix.clone(), line.clone()])
+                        .iter()
+                        .map(|v| {
+                            if is_last || v.position() < onset || !is_pending(v.rule()) {
+                                VIOLATION_COST
+                            } else {
+                                PENDING_COST
+                            }
+                        })
+                        .sum();
+                    let melodic_cost = candidate.melodic_cost
+                        + candidate
+                            .line
+                            .notes()
+                            .last()
+                            .map_or(0., |&previous| motion_cost(previous, note));
+                    next.push(Candidate {
+                        line,
+                        melodic_cost,
+                        cost: melodic_cost + violation_cost + rng.gen::<f64>(),
+                    });
+                }
+            }
+            next.sort_by(|a, b| a.cost.partial_cmp(&b.cost).unwrap());
+            next.truncate(self.beam_width);
+            beam = next;
+        }
+
+        beam.into_iter()
+            .map(|candidate| candidate.line)
+            .find(|line| {
+                self.checker
+                    .check(&[cantus.clone(), line.clone()])
+                    .is_empty()
+            })
+    }
+
+    /// Onsets and durations for each note of the line, given the length of the cantus.
+    fn rhythm<R: Rng>(&self, bars: usize, rng: &mut R) -> Vec<(BeatTime, BeatDuration)> {
+        let bar = self.checker.bar();
+        let mut slots = Vec::new();
+        let mut onset = BeatTime::zero();
+        for ix in 0..bars {
+            let is_last = ix + 1 == bars;
+            let durations: Vec<BeatDuration> = match self.checker.species() {
+                _ if is_last => vec![bar],
+                Species::First => vec![bar],
+                Species::Second => vec![bar / 2; 2],
+                Species::Third => vec![bar / 4; 4],
+                // Each note is tied over the bar line, after an opening rest, up to the
+                // penultimate note leading into the final on the bar line.
+                Species::Fourth if ix + 2 == bars => {
+                    if ix == 0 {
+                        onset += bar / 2;
+                    }
+                    vec![bar / 2]
+                }
+                Species::Fourth if ix == 0 => {
+                    onset += bar / 2;
+                    vec![bar]
+                }
+                Species::Fourth => vec![bar],
+                Species::Fifth => match rng.gen_range(0, 4) {
+                    0 => vec![bar],
+                    1 => vec![bar / 2; 2],
+                    2 => vec![bar / 2, bar / 4, bar / 4],
+                    _ => vec![bar / 4; 4],
+                },
+            };
+            for duration in durations {
+                slots.push((onset, duration));
+                onset += duration;
+            }
+        }
+        slots
+    }
+
+    /// Notes of `key` within range. The line ends on the tonic, which it may approach from a raised
+    /// leading tone, or where the cantus does not end in the key, on a note of the key doubling its
+    /// final or a fifth below it.
+    fn candidate_notes(
+        &self,
+        key: &Key,
+        cantus_final: Note,
+        is_penultimate: bool,
+        is_last: bool,
+    ) -> Vec<Note> {
+        let tonic = key.tonic().pitch_class();
+        let finals = [
+            tonic,
+            cantus_final.pitch_class(),
+            cantus_final.pitch_class() - PER_5,
+        ];
+        let lower = usize::from(self.range.lower_sounding());
+        let upper = usize::from(self.range.upper_sounding());
+        let in_range = || (lower..=upper).map(|n| Note::new(n as i8));
+        let mut notes: Vec<Note> = in_range()
+            .filter(|note| key.scale().contains(&(note.pitch_class() - tonic)))
+            .filter(|note| !is_last || finals.contains(&note.pitch_class()))
+            .collect();
+        if is_penultimate {
+            let leading_tone = tonic - MIN_2;
+            for note in in_range() {
+                if note.pitch_class() == leading_tone && !notes.contains(&note) {
+                    notes.push(note);
+                }
+            }
+        }
+        notes
+    }
+}
+
+/// Whether a violation at the newest note may still be fixed by the notes after it.
+fn is_pending(rule: CounterpointRule) -> bool {
+    matches!(
+        rule,
+        CounterpointRule::DissonantWeakBeat
+            | CounterpointRule::UnrecoveredLeap
+            | CounterpointRule::UnresolvedSuspension
+            | CounterpointRule::ImperfectBoundary
+    )
+}
+
+/// Prefers steps over leaps and movement over repetition.
+fn motion_cost(from: Note, to: Note) -> f64 {
+    match (to - from).semitones().abs() {
+        0 => 3.,
+        1 | 2 => 0.,
+        semitones => f64::from(semitones) / 2.,
+    }
+}
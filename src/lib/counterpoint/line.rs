@@ -0,0 +1,73 @@
+use super::super::{BeatDuration, BeatTime, Note};
+use super::Line;
+
+impl Line {
+    pub fn new() -> Line {
+        Line::default()
+    }
+
+    /// Notes of equal `duration` one after the other from time zero, e.g. a cantus firmus.
+    pub fn even(notes: &[Note], duration: BeatDuration) -> Line {
+        let mut line = Line::new();
+        let mut onset = BeatTime::zero();
+        for &note in notes {
+            line.push(note, onset, duration);
+            onset += duration;
+        }
+        line
+    }
+
+    /// Adds a note, which should start no earlier than the end of the previous one.
+    pub fn push(&mut self, note: Note, onset: BeatTime, duration: BeatDuration) {
+        self.notes.push(note);
+        self.onsets.push(onset);
+        self.durations.push(duration);
+    }
+
+    pub fn len(&self) -> usize {
+        self.notes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.notes.is_empty()
+    }
+
+    pub fn notes(&self) -> &[Note] {
+        &self.notes
+    }
+
+    pub fn onsets(&self) -> &[BeatTime] {
+        &self.onsets
+    }
+
+    pub fn durations(&self) -> &[BeatDuration] {
+        &self.durations
+    }
+
+    /// When the last note ends.
+    pub fn end(&self) -> BeatTime {
+        match self.notes.len() {
+            0 => BeatTime::zero(),
+            n => self.onsets[n - 1] + self.durations[n - 1],
+        }
+    }
+
+    /// The notes starting before `end`, the last one cut short if it would sound past it.
+    pub fn truncated(&self, end: BeatTime) -> Line {
+        let mut line = Line::new();
+        for i in 0..self.len() {
+            let onset = self.onsets[i];
+            if onset >= end {
+                break;
+            }
+            line.push(self.notes[i], onset, (end - onset).min(self.durations[i]));
+        }
+        line
+    }
+
+    /// The index of the note sounding at `time`, if any.
+    pub fn sounding_at(&self, time: BeatTime) -> Option<usize> {
+        let ix = self.onsets.iter().rposition(|&onset| onset <= time)?;
+        Some(ix).filter(|&ix| time < self.onsets[ix] + self.durations[ix])
+    }
+}
@@ -0,0 +1,68 @@
+mod checker;
+mod generator;
+mod line;
+
+use super::{BeatDuration, BeatTime, ChromaticRange, Note};
+
+/// A melody as notes starting on beats and lasting for durations, like the generator's tracks.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Line {
+    notes: Vec<Note>,
+    onsets: Vec<BeatTime>,
+    durations: Vec<BeatDuration>,
+}
+
+/// https://en.wikipedia.org/wiki/Counterpoint#Species_counterpoint
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Species {
+    /// Note against note.
+    First,
+    /// Two notes against each note of the cantus firmus.
+    Second,
+    /// Four notes against each note of the cantus firmus.
+    Third,
+    /// Syncopated notes tied over the bar line, forming suspensions.
+    Fourth,
+    /// Florid counterpoint, mixing the other species.
+    Fifth,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CounterpointRule {
+    DissonantStrongBeat,
+    DissonantWeakBeat,
+    ParallelPerfects,
+    UnpreparedSuspension,
+    UnresolvedSuspension,
+    UnrecoveredLeap,
+    DissonantLeap,
+    ImperfectBoundary,
+    OutOfRange,
+    WrongRhythm,
+}
+
+/// A broken rule at `position`, involving the lines at the given indices.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CounterpointViolation {
+    rule: CounterpointRule,
+    position: BeatTime,
+    lines: Vec<usize>,
+}
+
+/// Checks lines of species counterpoint, the first line being the cantus firmus with one note per
+/// bar of length `bar`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CounterpointChecker {
+    species: Species,
+    bar: BeatDuration,
+    ranges: Vec<ChromaticRange>,
+}
+
+/// Writes a line against a cantus firmus by beam search, keeping the candidates the checker
+/// objects to least. The line keeps to the notes of a key, ending on its tonic.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CounterpointGenerator {
+    checker: CounterpointChecker,
+    range: ChromaticRange,
+    beam_width: usize,
+}
@@ -0,0 +1,41 @@
+use super::super::BeatTime;
+use super::DissonanceCurve;
+
+impl DissonanceCurve {
+    pub fn constant(dissonance: f64) -> DissonanceCurve {
+        DissonanceCurve {
+            points: vec![(BeatTime::zero(), dissonance.clamp(0., 1.))],
+        }
+    }
+
+    /// Moves to `dissonance` at `time`, replacing any point already there.
+    pub fn with_point(&self, time: BeatTime, dissonance: f64) -> DissonanceCurve {
+        let mut points = self.points.clone();
+        points.retain(|&(t, _)| t != time);
+        let ix = points
+            .iter()
+            .position(|&(t, _)| t > time)
+            .unwrap_or(points.len());
+        points.insert(ix, (time, dissonance.clamp(0., 1.)));
+        DissonanceCurve { points }
+    }
+
+    pub fn points(&self) -> &[(BeatTime, f64)] {
+        &self.points
+    }
+
+    /// The target at `time`, holding the first and last points before and after the curve.
+    pub fn at(&self, time: BeatTime) -> f64 {
+        let ix = self.points.iter().position(|&(t, _)| t > time);
+        match ix {
+            Some(0) => self.points[0].1,
+            Some(ix) => {
+                let (t0, d0) = self.points[ix - 1];
+                let (t1, d1) = self.points[ix];
+                let x = (f64::from(time) - f64::from(t0)) / (f64::from(t1) - f64::from(t0));
+                d0 + (d1 - d0) * x
+            }
+            None => self.points.last().map_or(0., |&(_, d)| d),
+        }
+    }
+}
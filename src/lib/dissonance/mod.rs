@@ -0,0 +1,21 @@
+mod dissonance_curve;
+mod roughness;
+
+use super::BeatTime;
+
+/// https://en.wikipedia.org/wiki/Consonance_and_dissonance#Physiological_basis
+///
+/// Sensory roughness between the partials of harmonic tones, following the curve of Plomp and
+/// Levelt as parametrised by Sethares.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Roughness {
+    partials: usize,
+    rolloff: f64,
+}
+
+/// A target dissonance changing over time, from 0 for smooth to 1 for rough, with straight lines
+/// between the points.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DissonanceCurve {
+    points: Vec<(BeatTime, f64)>,
+}
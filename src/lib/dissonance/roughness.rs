@@ -0,0 +1,99 @@
+use super::super::{Chord, Interval, Note};
+use super::Roughness;
+
+/// Sethares' fit of the Plomp-Levelt curve.
+const B1: f64 = 3.5;
+const B2: f64 = 5.75;
+const S1: f64 = 0.0207;
+const S2: f64 = 18.96;
+const X_STAR: f64 = 0.24;
+
+impl Roughness {
+    /// Tones of `partials` harmonics, each quieter than the one below by the factor `rolloff`.
+    pub fn new(partials: usize, rolloff: f64) -> Roughness {
+        assert!(partials > 0);
+        Roughness { partials, rolloff }
+    }
+
+    pub fn partials(&self) -> usize {
+        self.partials
+    }
+
+    pub fn rolloff(&self) -> f64 {
+        self.rolloff
+    }
+
+    /// The total roughness of `notes` sounding together, summed over every pair of partials.
+    pub fn of_notes(&self, notes: &[Note]) -> f64 {
+        let mut total = 0.;
+        for (i, &a) in notes.iter().enumerate() {
+            for &b in notes[i + 1..].iter() {
+                total += self.of_pair(a, b);
+            }
+        }
+        total
+    }
+
+    /// The mean roughness of each pair of `notes`, relative to a minor second up from the lower
+    /// note of the pair, so near 0 for unisons and octaves, and at most 1.
+    pub fn dissonance(&self, notes: &[Note]) -> f64 {
+        let mut total = 0.;
+        let mut pairs = 0;
+        for (i, &a) in notes.iter().enumerate() {
+            for &b in notes[i + 1..].iter() {
+                let lower = a.min(b);
+                let semitone = self.of_pair(lower, lower.saturating_add(Interval::new(1)));
+                total += (self.of_pair(a, b) / semitone).min(1.);
+                pairs += 1;
+            }
+        }
+        if pairs == 0 {
+            0.
+        } else {
+            total / pairs as f64
+        }
+    }
+
+    /// The dissonance of `chord` voiced as by `Chord::notes`.
+    pub fn of_chord(&self, chord: &Chord, octave: i8) -> f64 {
+        self.dissonance(&chord.notes(octave))
+    }
+
+    /// The dissonance of every degree of `scale` from `tonic` sounding together, e.g. to compare
+    /// modes.
+    pub fn of_scale(&self, scale: &[Interval], tonic: Note) -> f64 {
+        let notes: Vec<Note> = scale.iter().map(|&i| tonic.saturating_add(i)).collect();
+        self.dissonance(&notes)
+    }
+
+    fn of_pair(&self, a: Note, b: Note) -> f64 {
+        let mut total = 0.;
+        let mut amplitude_a = 1.;
+        for k in 1..=self.partials {
+            let mut amplitude_b = 1.;
+            for l in 1..=self.partials {
+                total += partial_roughness(
+                    a.pitch() * k as f64,
+                    b.pitch() * l as f64,
+                    amplitude_a * amplitude_b,
+                );
+                amplitude_b *= self.rolloff;
+            }
+            amplitude_a *= self.rolloff;
+        }
+        total
+    }
+}
+
+/// Six partials falling off by 0.88, as Sethares suggests.
+impl Default for Roughness {
+    fn default() -> Self {
+        Roughness::new(6, 0.88)
+    }
+}
+
+fn partial_roughness(f1: f64, f2: f64, amplitude: f64) -> f64 {
+    let s = X_STAR / (S1 * f1.min(f2) + S2);
+    let x = s * (f1 - f2).abs();
+    amplitude * ((-B1 * x).exp() - (-B2 * x).exp())
+}
@@ -0,0 +1,163 @@
+use std::collections::HashMap;
+
+use super::{Form, FormScheduler, Section};
+
+impl<T: Clone> FormScheduler<T> {
+    pub fn new(form: Form) -> FormScheduler<T> {
+        FormScheduler {
+            form,
+            material: HashMap::new(),
+            section_ix: 0,
+            measure_ix: 0,
+        }
+    }
+
+    pub fn form(&self) -> &Form {
+        &self.form
+    }
+
+    /// The section the next measure belongs to, and its index within the section.
+    pub fn position(&self) -> (Section, usize) {
+        (self.form.sections()[self.section_ix], self.measure_ix)
+    }
+
+    /// Whether the next measure starts a section.
+    pub fn is_section_start(&self) -> bool {
+        self.measure_ix == 0
+    }
+
+    /// Whether the section the next measure belongs to is marked for a solo.
+    pub fn is_solo(&self) -> bool {
+        self.form.is_solo(self.section_ix)
+    }
+
+    /// The material of the measure in `section` at `measure`, if generated already.
+    pub fn material(&self, section: Section, measure: usize) -> Option<&T> {
+        self.material.get(&section)?.get(measure)
+    }
+
+    /// The next measure of the form along with its section.
+    ///
+    /// Material is taken from the same section played before, or else made by `make`. `make` gets
+    /// the section, the index of the measure within it, and the material of the closest earlier
+    /// variation of the section to vary, if any.
+    pub fn next<F>(&mut self, make: F) -> (Section, T)
+    where
+        F: FnOnce(Section, usize, Option<&T>) -> T,
+    {
+        let (section, measure) = self.position();
+        let measure_material = match self.material(section, measure) {
+            Some(material) => material.clone(),
+            None => {
+                let source = (0..section.variation()).rev().find_map(|variation| {
+                    self.material(Section::new(section.letter(), variation), measure)
+                });
+                let material = make(section, measure, source);
+                self.material
+                    .entry(section)
+                    .or_default()
+                    .push(material.clone());
+                material
+            }
+        };
+
+        self.measure_ix += 1;
+        if self.measure_ix >= self.form.measures(section) {
+            self.measure_ix = 0;
+            self.section_ix = (self.section_ix + 1) % self.form.sections().len();
+        }
+        (section, measure_material)
+    }
+}
+
+/******************************************************************************
+* TESTS
+******************************************************************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Plays `measures` measures of `form`, making each measure of new material from its section,
+    /// measure index and source.
+    fn play(form: Form, measures: usize) -> Vec<(Section, String)> {
+        let mut scheduler = FormScheduler::new(form);
+        (0..measures)
+            .map(|_| {
+                scheduler.next(|section, measure, source: Option<&String>| match source {
+                    Some(source) => format!("{} from {}", section, source),
+                    None => format!("{}{}", section, measure),
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn replays_and_varies_sections() {
+        let played = play(Form::chain(2, 2), 14);
+        let material: Vec<&str> = played.iter().map(|(_, m)| m.as_str()).collect();
+        assert_eq!(
+            material,
+            [
+                "B0",
+                "B1",
+                "A0",
+                "A1",
+                "B' from B0",
+                "B' from B1",
+                "C0",
+                "C1",
+                "B0",
+                "B1",
+                "C' from C0",
+                "C' from C1",
+                "B0",
+                "B1",
+            ]
+        );
+        let sections: Vec<String> = played.iter().map(|(s, _)| s.to_string()).collect();
+        assert_eq!(sections[4..8], ["B'", "B'", "C", "C"]);
+    }
+
+    #[test]
+    fn varies_the_closest_earlier_variation() {
+        let form: Form = "A A'' A' A'''".parse().unwrap();
+        let played = play(form.with_measures(Section::new(0, 0), 1), 4);
+        let material: Vec<&str> = played.iter().map(|(_, m)| m.as_str()).collect();
+        assert_eq!(
+            material,
+            ["A0", "A'' from A0", "A' from A0", "A''' from A'' from A0"]
+        );
+    }
+
+    #[test]
+    fn positions_and_solos() {
+        let form: Form = "A B*".parse().unwrap();
+        let form = form
+            .with_measures(Section::new(0, 0), 1)
+            .with_measures(Section::new(1, 0), 2);
+        let mut scheduler = FormScheduler::new(form);
+        let mut positions = Vec::new();
+        for _ in 0..4 {
+            positions.push((
+                scheduler.position(),
+                scheduler.is_section_start(),
+                scheduler.is_solo(),
+            ));
+            scheduler.next(|_, measure, _| measure);
+        }
+        let a = Section::new(0, 0);
+        let b = Section::new(1, 0);
+        assert_eq!(
+            positions,
+            [
+                ((a, 0), true, false),
+                ((b, 0), true, true),
+                ((b, 1), false, true),
+                ((a, 0), true, false),
+            ]
+        );
+        assert_eq!(scheduler.material(b, 1), Some(&1));
+        assert_eq!(scheduler.material(b, 2), None);
+    }
+}
@@ -0,0 +1,37 @@
+mod form_scheduler;
+mod musical_form;
+mod section;
+
+pub use section::*;
+
+use std::collections::HashMap;
+
+/// A section of a form, labelled by letter, with primes marking variations, e.g. `B'`.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Section {
+    letter: u8,
+    variation: u8,
+}
+
+/// https://en.wikipedia.org/wiki/Musical_form
+///
+/// Sections in the order they are played, with the length in measures of each letter. Each section
+/// played can be marked for a soloist to improvise over.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Form {
+    sections: Vec<Section>,
+    solos: Vec<bool>,
+    measures: HashMap<u8, usize>,
+    default_measures: usize,
+}
+
+/// Steps through a form measure by measure, generating the material of each section the first time
+/// it comes up and replaying it after that, or varying it for primed sections. The form repeats
+/// from the top once it ends.
+#[derive(Clone, Debug)]
+pub struct FormScheduler<T> {
+    form: Form,
+    material: HashMap<Section, Vec<T>>,
+    section_ix: usize,
+    measure_ix: usize,
+}
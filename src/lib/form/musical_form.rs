@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+
+use super::{Form, ParseFormError, Section};
+
+impl Form {
+    /// Every section lasting `default_measures` until set otherwise.
+    pub fn new(sections: &[Section], default_measures: usize) -> Form {
+        assert!(!sections.is_empty());
+        assert!(default_measures > 0);
+        Form {
+            sections: sections.to_vec(),
+            solos: vec![false; sections.len()],
+            measures: HashMap::new(),
+            default_measures,
+        }
+    }
+
+    /// `B A B' -> C B C' -> D C D' -> ...` for `links` links, each bringing back the new section of
+    /// the link before between a new section and its variation.
+    pub fn chain(links: usize, default_measures: usize) -> Form {
+        assert!(links > 0 && links < Section::LETTER_COUNT as usize);
+        let sections: Vec<Section> = (0..links as u8)
+            .flat_map(|link| {
+                let new = Section::new(link + 1, 0);
+                vec![new, Section::new(link, 0), new.varied()]
+            })
+            .collect();
+        Form::new(&sections, default_measures)
+    }
+
+    /// Sets how long all sections of the letter of `section` last, variations included.
+    pub fn with_measures(&self, section: Section, measures: usize) -> Form {
+        assert!(measures > 0);
+        let mut form = self.clone();
+        form.measures.insert(section.letter(), measures);
+        form
+    }
+
+    /// Marks the section at `ix` in the order played for a solo, or unmarks it.
+    pub fn with_solo(&self, ix: usize, solo: bool) -> Form {
+        let mut form = self.clone();
+        form.solos[ix] = solo;
+        form
+    }
+
+    pub fn sections(&self) -> &[Section] {
+        &self.sections
+    }
+
+    /// Whether the section at `ix` in the order played is marked for a solo.
+    pub fn is_solo(&self, ix: usize) -> bool {
+        self.solos[ix]
+    }
+
+    pub fn measures(&self, section: Section) -> usize {
+        *self
+            .measures
+            .get(&section.letter())
+            .unwrap_or(&self.default_measures)
+    }
+
+    /// The length of the whole form in measures.
+    pub fn total_measures(&self) -> usize {
+        self.sections.iter().map(|&s| self.measures(s)).sum()
+    }
+}
+
+/// Prints the sections separated by spaces, with solos marked by `*`, e.g. `B A* B' C B C'`.
+impl std::fmt::Display for Form {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for (i, section) in self.sections.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{}", section)?;
+            if self.solos[i] {
+                f.write_str("*")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Parses sections separated by whitespace, ignoring `->` between links, each lasting four
+/// measures. Sections ending in `*` are marked for a solo.
+impl std::str::FromStr for Form {
+    type Err = ParseFormError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tokens: Vec<&str> = s
+            .split_whitespace()
+            .filter(|token| *token != "->" && *token != "→")
+            .collect();
+        let sections = tokens
+            .iter()
+            .map(|token| token.trim_end_matches('*').parse())
+            .collect::<Result<Vec<Section>, _>>()?;
+        if sections.is_empty() {
+            return Err(ParseFormError::Empty);
+        }
+        let mut form = Form::new(&sections, 4);
+        for (ix, token) in tokens.iter().enumerate() {
+            form.solos[ix] = token.ends_with('*');
+        }
+        Ok(form)
+    }
+}
+
+/******************************************************************************
+* TESTS
+******************************************************************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chains_of_links() {
+        assert_eq!(Form::chain(1, 4).to_string(), "B A B'");
+        assert_eq!(Form::chain(2, 4).to_string(), "B A B' C B C'");
+        assert_eq!(Form::chain(3, 4).to_string(), "B A B' C B C' D C D'");
+        assert_eq!(Form::chain(2, 4), "B A B' -> C B C'".parse().unwrap());
+    }
+
+    #[test]
+    fn forms_round_trip() {
+        for s in ["A", "A A' B A''", "B A* B' C B* C'"].iter() {
+            let form: Form = s.parse().unwrap();
+            assert_eq!(form.to_string(), *s);
+        }
+        let form: Form = "A B′ → A*".parse().unwrap();
+        assert_eq!(form.to_string(), "A B' A*");
+        assert!(form.is_solo(2) && !form.is_solo(0));
+        assert_eq!(form.sections()[1], Section::new(1, 1));
+    }
+
+    #[test]
+    fn measures_by_letter() {
+        let form = Form::chain(2, 4).with_measures(Section::new(1, 0), 8);
+        assert_eq!(form.measures(Section::new(1, 1)), 8);
+        assert_eq!(form.measures(Section::new(2, 0)), 4);
+        assert_eq!(form.total_measures(), 8 + 4 + 8 + 4 + 8 + 4);
+    }
+
+    #[test]
+    fn invalid_forms() {
+        assert_eq!("".parse::<Form>(), Err(ParseFormError::Empty));
+        assert_eq!("->".parse::<Form>(), Err(ParseFormError::Empty));
+        for s in ["a", "A B1", "A 'B", "A B*'", "Ä"].iter() {
+            assert!(s.parse::<Form>().is_err(), "{}", s);
+        }
+        let primes = format!("A{}", "'".repeat(256));
+        assert_eq!(
+            primes.parse::<Section>(),
+            Err(ParseFormError::InvalidSection(primes))
+        );
+        assert_eq!(
+            format!("A{}", "'".repeat(255)).parse::<Section>(),
+            Ok(Section::new(0, 255))
+        );
+    }
+}
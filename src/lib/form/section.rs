@@ -0,0 +1,94 @@
+use super::Section;
+
+/******************************************************************************
+* ERRORS
+******************************************************************************/
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParseFormError {
+    Empty,
+    InvalidSection(String),
+}
+
+impl std::fmt::Display for ParseFormError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ParseFormError::Empty => write!(f, "expected sections, found nothing"),
+            ParseFormError::InvalidSection(s) => {
+                write!(f, "invalid section \"{}\", expected a letter and primes", s)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseFormError {}
+
+/******************************************************************************
+* IMPLS
+******************************************************************************/
+
+impl Section {
+    pub const LETTER_COUNT: u8 = 26;
+
+    /// `letter` counts from 0 for `A`.
+    pub fn new(letter: u8, variation: u8) -> Section {
+        assert!(letter < Section::LETTER_COUNT);
+        Section { letter, variation }
+    }
+
+    pub fn letter(self) -> u8 {
+        self.letter
+    }
+
+    /// How many primes the section has, 0 for the original.
+    pub fn variation(self) -> u8 {
+        self.variation
+    }
+
+    /// The section with one more prime.
+    pub fn varied(self) -> Section {
+        Section::new(self.letter, self.variation + 1)
+    }
+
+    /// The section without primes.
+    pub fn original(self) -> Section {
+        Section::new(self.letter, 0)
+    }
+}
+
+/// Prints e.g. `B''`.
+impl std::fmt::Display for Section {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", (b'A' + self.letter) as char)?;
+        for _ in 0..self.variation {
+            f.write_str("'")?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses e.g. `B'`, also accepting `′` as a prime.
+impl std::str::FromStr for Section {
+    type Err = ParseFormError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.trim().chars();
+        let letter = match chars.next() {
+            None => return Err(ParseFormError::Empty),
+            Some(c) if c.is_ascii_uppercase() => c as u8 - b'A',
+            Some(_) => return Err(ParseFormError::InvalidSection(s.to_string())),
+        };
+        let mut variation: u8 = 0;
+        for c in chars {
+            match c {
+                '\'' | '′' => {
+                    variation = variation
+                        .checked_add(1)
+                        .ok_or_else(|| ParseFormError::InvalidSection(s.to_string()))?
+                }
+                _ => return Err(ParseFormError::InvalidSection(s.to_string())),
+            }
+        }
+        Ok(Section::new(letter, variation))
+    }
+}
@@ -0,0 +1,765 @@
+use super::super::spelled_interval_constants::*;
+use super::super::{
+    parse_spelled_pitch_class, Interval, IntervalQuality, Note, ParsePitchError, PitchClass,
+    PitchClassSet, ScaleFamily, SpelledInterval, SpelledPitch, SpelledPitchClass, CHROMATIC_COUNT,
+    DIATONIC_COUNT,
+};
+use super::{strip_accidental, strip_any, Chord};
+
+/******************************************************************************
+* ERRORS
+******************************************************************************/
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParseChordError {
+    Empty,
+    InvalidRoot(ParsePitchError),
+    InvalidBass(ParsePitchError),
+    UnexpectedToken(String),
+    InvalidNumber(u8),
+    ConflictingTokens(String, String),
+    NothingToOmit(u8),
+}
+
+impl std::fmt::Display for ParseChordError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ParseChordError::Empty => write!(f, "expected a chord symbol, found nothing"),
+            ParseChordError::InvalidRoot(e) => write!(f, "invalid root: {}", e),
+            ParseChordError::InvalidBass(e) => write!(f, "invalid bass note: {}", e),
+            ParseChordError::UnexpectedToken(s) => {
+                write!(f, "unexpected \"{}\" in chord symbol", s)
+            }
+            ParseChordError::InvalidNumber(n) => write!(f, "{} is not a chord tone", n),
+            ParseChordError::ConflictingTokens(a, b) => {
+                write!(f, "\"{}\" cannot be combined with \"{}\"", a, b)
+            }
+            ParseChordError::NothingToOmit(n) => {
+                write!(f, "cannot omit the {}, the chord does not have one", n)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseChordError {}
+
+/******************************************************************************
+* IMPLS
+******************************************************************************/
+
+impl Chord {
+    /// `intervals` are measured from `root` and get sorted low to high.
+    pub fn new(root: SpelledPitchClass, intervals: &[SpelledInterval]) -> Chord {
+        let mut intervals = intervals.to_vec();
+        intervals.sort_by_key(|i| (i.interval(), i.number()));
+        intervals.dedup();
+        Chord {
+            root,
+            intervals,
+            bass: None,
+        }
+    }
+
+    /// Stacks `size` thirds from the first degree of a heptatonic `chord_scale` on `root`, so 3 gives
+    /// a triad, 4 a seventh chord and 7 a thirteenth chord.
+    pub fn stacked_thirds(root: SpelledPitchClass, chord_scale: &[Interval], size: usize) -> Chord {
+        assert_eq!(chord_scale.len(), DIATONIC_COUNT);
+        let intervals: Vec<SpelledInterval> = (1..size)
+            .map(|k| {
+                let step = 2 * k;
+                let semitones = chord_scale[step % DIATONIC_COUNT].semitones()
+                    + (step / DIATONIC_COUNT * CHROMATIC_COUNT) as i8;
+                SpelledInterval::with_number(step as u8 + 1, Interval::new(semitones))
+            })
+            .collect();
+        Chord::new(root, &intervals)
+    }
+
+    /// The chord of `size` stacked thirds on `scale_degree` of `mode` in the key of `tonic`.
+    pub fn diatonic(
+        family: &ScaleFamily,
+        mode: usize,
+        scale_degree: usize,
+        tonic: SpelledPitchClass,
+        size: usize,
+    ) -> Chord {
+        let root = family.spelled_scale(mode, tonic)[scale_degree];
+        Chord::stacked_thirds(root, family.chord_scale_0(mode, scale_degree), size)
+    }
+
+    /// Names an unspelled set of notes, taking the lowest one as the bass.
+    ///
+    /// Roots are spelled as they are usually named as keys, e.g. `Eb` rather than `D#`.
+    pub fn recognise(notes: &[Note]) -> Option<Chord> {
+        let bass = *notes.iter().min()?;
+        let (root, intervals) =
+            recognise_pitch_classes(PitchClassSet::from_notes(notes), bass.pitch_class());
+        Some(
+            Chord::new(SpelledPitchClass::common(root), &intervals)
+                .with_bass_pitch_class(bass.pitch_class()),
+        )
+    }
+
+    /// Names a set of spelled pitches, taking the lowest one as the bass and keeping the spelling of the root.
+    pub fn recognise_spelled(pitches: &[SpelledPitch]) -> Option<Chord> {
+        let bass = *pitches.iter().min_by_key(|p| p.midi_number())?;
+        let set = pitches
+            .iter()
+            .map(|p| p.class().pitch_class())
+            .collect::<PitchClassSet>();
+        let (root, intervals) = recognise_pitch_classes(set, bass.class().pitch_class());
+        let root = pitches
+            .iter()
+            .map(|p| p.class())
+            .find(|c| c.pitch_class() == root)
+            .unwrap();
+        Some(Chord::new(root, &intervals).with_bass_pitch_class(bass.class().pitch_class()))
+    }
+
+    pub fn root(&self) -> SpelledPitchClass {
+        self.root
+    }
+
+    /// Intervals above the root, low to high.
+    pub fn intervals(&self) -> &[SpelledInterval] {
+        &self.intervals
+    }
+
+    /// The lowest sounding pitch class, which is the root unless the chord is inverted.
+    pub fn bass(&self) -> SpelledPitchClass {
+        self.bass.unwrap_or(self.root)
+    }
+
+    /// Chord tones from the root upwards.
+    pub fn tones(&self) -> Vec<SpelledPitchClass> {
+        std::iter::once(self.root)
+            .chain(self.intervals.iter().map(|&i| self.root.transpose(i)))
+            .collect()
+    }
+
+    pub fn pitch_class_set(&self) -> PitchClassSet {
+        self.tones().iter().map(|t| t.pitch_class()).collect()
+    }
+
+    /// The interval of the given generic number (or its compound) above the root, if any.
+    pub fn chord_tone(&self, number: u8) -> Option<SpelledInterval> {
+        self.intervals
+            .iter()
+            .copied()
+            .find(|i| i.number() == number || i.simple().number() == number)
+    }
+
+    pub fn third(&self) -> Option<SpelledInterval> {
+        self.chord_tone(3)
+    }
+
+    pub fn fifth(&self) -> Option<SpelledInterval> {
+        self.chord_tone(5)
+    }
+
+    pub fn seventh(&self) -> Option<SpelledInterval> {
+        self.chord_tone(7)
+    }
+
+    /// Puts the `n`th chord tone in the bass, so 0 is root position and 1 is first inversion.
+    pub fn inversion(&self, n: usize) -> Chord {
+        let bass = self.tones()[n % (self.intervals.len() + 1)];
+        Chord {
+            bass: if n == 0 { None } else { Some(bass) },
+            ..self.clone()
+        }
+    }
+
+    /// Puts `bass` below the chord, which need not be a chord tone.
+    pub fn with_bass(&self, bass: SpelledPitchClass) -> Chord {
+        Chord {
+            bass: if bass == self.root { None } else { Some(bass) },
+            ..self.clone()
+        }
+    }
+
+    pub fn transpose(&self, interval: SpelledInterval) -> Chord {
+        Chord {
+            root: self.root.transpose(interval),
+            intervals: self.intervals.clone(),
+            bass: self.bass.map(|b| b.transpose(interval)),
+        }
+    }
+
+    /// A close voicing with the root in `octave`, and the bass, if any, right below it.
+    pub fn notes(&self, octave: i8) -> Vec<Note> {
+        let root = match Note::from_pitch_class(self.root.pitch_class(), octave) {
+            Some(root) => root,
+            None => return Vec::new(),
+        };
+        let bass = self
+            .bass
+            .and_then(|b| root.checked_sub(self.root.pitch_class() - b.pitch_class()));
+        bass.into_iter()
+            .chain(std::iter::once(root))
+            .chain(
+                self.intervals
+                    .iter()
+                    .filter_map(|&i| root.checked_add(i.interval())),
+            )
+            .collect()
+    }
+
+    /// The canonical lead sheet name of the chord quality without root or bass, e.g. `m7` or
+    /// `7(b9,#11)`.
+    pub fn suffix(&self) -> String {
+        let mut rest = self.intervals.clone();
+        let third = [MAJ_3, MIN_3].iter().copied().find(|&i| take(&mut rest, i));
+        let fifth = [PER_5, DIM_5, AUG_5]
+            .iter()
+            .copied()
+            .find(|&i| take(&mut rest, i));
+        let mut seventh = [MIN_7, MAJ_7, DIM_7]
+            .iter()
+            .copied()
+            .find(|&i| take(&mut rest, i));
+
+        if (third, fifth, seventh, rest.is_empty()) == (None, Some(PER_5), None, true) {
+            return "5".to_string();
+        }
+
+        let quality = match (third, fifth, seventh) {
+            (Some(MIN_3), Some(DIM_5), Some(MIN_7)) => "ø",
+            (Some(MIN_3), Some(DIM_5), _) => "°",
+            (Some(MAJ_3), Some(AUG_5), _) => "+",
+            (Some(MIN_3), _, _) => "m",
+            _ => "",
+        };
+        if seventh == Some(DIM_7) && quality != "°" {
+            rest.push(DIM_7);
+            seventh = None;
+        }
+
+        let mut number = seventh.map(|_| 7);
+        let mut six_nine = false;
+        let has_number = |rest: &[SpelledInterval], n| rest.iter().any(|i| i.number() == n);
+        if seventh.is_some() && ["", "m", "+"].contains(&quality) {
+            if has_number(&rest, 9)
+                && rest.contains(&MAJ_13)
+                && (quality != "m" || has_number(&rest, 11))
+            {
+                number = Some(13);
+                take(&mut rest, MAJ_13);
+                take(&mut rest, MAJ_9);
+                if quality == "m" {
+                    take(&mut rest, PER_11);
+                }
+            } else if has_number(&rest, 9) && rest.contains(&PER_11) {
+                number = Some(11);
+                take(&mut rest, PER_11);
+                take(&mut rest, MAJ_9);
+            } else if take(&mut rest, MAJ_9) {
+                number = Some(9);
+            }
+        } else if seventh.is_none() && ["", "m"].contains(&quality) && take(&mut rest, MAJ_6) {
+            number = Some(6);
+            six_nine = take(&mut rest, MAJ_9);
+        }
+
+        let mut name = quality.to_string();
+        match (seventh, number) {
+            (Some(MAJ_7), Some(n)) if quality.is_empty() => name += &format!("maj{}", n),
+            (Some(MAJ_7), Some(n)) => name += &format!("(maj{})", n),
+            (_, Some(6)) if six_nine => name += "6/9",
+            (_, Some(n)) => name += &n.to_string(),
+            _ => {}
+        }
+
+        let mut items = Vec::new();
+        if third.is_none() {
+            if take(&mut rest, PER_4) {
+                name += "sus4";
+            } else if take(&mut rest, MAJ_2) {
+                name += "sus2";
+            }
+        }
+        match (quality, fifth) {
+            ("", Some(DIM_5)) | ("m", Some(DIM_5)) => items.push("b5".to_string()),
+            ("", Some(AUG_5)) | ("m", Some(AUG_5)) => items.push("#5".to_string()),
+            _ => {}
+        }
+        let (alterations, additions): (Vec<SpelledInterval>, Vec<_>) = rest
+            .into_iter()
+            .partition(|&i| [9, 11, 13].contains(&i.number()) && i != natural(i.number()).unwrap());
+        for i in alterations {
+            items.push(format!("{}{}", accidental_name(i), i.number()));
+        }
+        for i in additions {
+            items.push(format!("add{}{}", accidental_name(i), i.number()));
+        }
+        if third.is_none() && !name.contains("sus") {
+            items.push("no3".to_string());
+        }
+        if fifth.is_none() {
+            items.push("no5".to_string());
+        }
+
+        if name.is_empty() && items.len() == 1 && items[0].starts_with("add") {
+            name += &items[0];
+        } else if name.ends_with(')') && !items.is_empty() {
+            name.insert_str(name.len() - 1, &format!(",{}", items.join(",")));
+        } else if !items.is_empty() {
+            name += &format!("({})", items.join(","));
+        }
+        name
+    }
+
+    fn with_bass_pitch_class(self, bass: PitchClass) -> Chord {
+        let bass = self.tones().into_iter().find(|t| t.pitch_class() == bass);
+        Chord {
+            bass: bass.filter(|&b| b != self.root),
+            ..self
+        }
+    }
+}
+
+/// Prints e.g. `Cmaj7/E`, `F#ø7` or `C7(b9,no5)`.
+impl std::fmt::Display for Chord {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}{}", self.root, self.suffix())?;
+        if let Some(bass) = self.bass {
+            write!(f, "/{}", bass)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses lead sheet symbols such as `Dm7`, `Bbmaj9#11`, `G7(b9,b13)`, `Fsus4/C` or `C6/9`.
+///
+/// Qualities may be written `m`/`min`/`-`, `maj`/`M`/`Δ`, `dim`/`°`/`o`, `aug`/`+` or `ø`, and
+/// `add`, `no` and `omit` add or remove chord tones. `alt` stands for `b9,#9,#11,b13,no5`.
+impl std::str::FromStr for Chord {
+    type Err = ParseChordError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(ParseChordError::Empty);
+        }
+        let (s, bass) = match s.rfind('/') {
+            Some(ix) if !s[ix + 1..].starts_with(|c: char| c.is_ascii_digit()) => {
+                let bass = s[ix + 1..]
+                    .parse::<SpelledPitchClass>()
+                    .map_err(ParseChordError::InvalidBass)?;
+                (&s[..ix], Some(bass))
+            }
+            _ => (s, None),
+        };
+        let (root, suffix) = parse_spelled_pitch_class(s).map_err(ParseChordError::InvalidRoot)?;
+        let chord = Chord::new(root, &parse_suffix(suffix)?);
+        Ok(match bass {
+            Some(bass) => chord.with_bass(bass),
+            None => chord,
+        })
+    }
+}
+
+/// Finds the root and the intervals above it that best explain `set`.
+///
+/// Exact matches beat ones missing the fifth, root position beats inversions, and earlier entries
+/// in `CHORD_SUFFIXES` beat later ones. Unknown sets are explained from the bass with common spellings.
+fn recognise_pitch_classes(
+    set: PitchClassSet,
+    bass: PitchClass,
+) -> (PitchClass, Vec<SpelledInterval>) {
+    use chord_constants::CHORD_SUFFIXES;
+
+    let mut best_cost = None;
+    let mut best = None;
+    for (suffix_ix, &(_, template)) in CHORD_SUFFIXES.iter().enumerate() {
+        for root in set.iter() {
+            let tones: PitchClassSet = std::iter::once(root)
+                .chain(template.iter().map(|&i| root + i.interval()))
+                .collect();
+            let fifth = root + PER_5.interval();
+            let omitted_fifth = template.contains(&PER_5) && !set.contains(fifth);
+            let omission = if tones == set {
+                0
+            } else if omitted_fifth && {
+                let mut without_fifth = tones;
+                without_fifth.remove(fifth);
+                without_fifth == set
+            } {
+                1
+            } else {
+                continue;
+            };
+
+            let cost = (omission, root != bass, suffix_ix);
+            if best_cost.is_some_and(|best_cost| best_cost <= cost) {
+                continue;
+            }
+            let intervals = template
+                .iter()
+                .copied()
+                .filter(|&i| omission == 0 || i != PER_5)
+                .collect();
+            best_cost = Some(cost);
+            best = Some((root, intervals));
+        }
+    }
+
+    match best {
+        Some(best) => best,
+        None => {
+            let intervals = set
+                .iter()
+                .filter(|&pc| pc != bass)
+                .map(|pc| SpelledInterval::from_interval(pc - bass))
+                .collect();
+            (bass, intervals)
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Triad {
+    Major,
+    Minor,
+    Diminished,
+    HalfDiminished,
+    Augmented,
+}
+
+/// Parses everything between the root and the bass into intervals above the root.
+fn parse_suffix(s: &str) -> Result<Vec<SpelledInterval>, ParseChordError> {
+    let (quality, mut rest) = match strip_any(s, &["maj", "Maj", "omit", "sus"]) {
+        Some(_) => ("", s),
+        None => strip_any(
+            s,
+            &["min", "mi", "m", "-", "dim", "°", "o", "aug", "+", "ø", "Ø"],
+        )
+        .unwrap_or(("", s)),
+    };
+    let triad = match quality {
+        "min" | "mi" | "m" | "-" => Triad::Minor,
+        "dim" | "°" | "o" => Triad::Diminished,
+        "aug" | "+" => Triad::Augmented,
+        "ø" | "Ø" => Triad::HalfDiminished,
+        _ => Triad::Major,
+    };
+
+    let mut major_seventh = None;
+    let mut extension = None;
+    let mut sus = None;
+    let mut alterations = Vec::new();
+    let mut additions = Vec::new();
+    let mut omissions = Vec::new();
+    while !rest.is_empty() {
+        let token = rest;
+        let unexpected = || ParseChordError::UnexpectedToken(token.to_string());
+        if let Some((_, r)) = strip_any(rest, &["(", ")", ","]) {
+            rest = r;
+        } else if let Some((t, r)) = strip_any(rest, &["maj", "Maj", "M", "Δ", "△"]) {
+            if major_seventh.is_some() || extension.is_some() {
+                return Err(unexpected());
+            }
+            major_seventh = Some(t);
+            rest = r;
+        } else if let Some((t, r)) = strip_any(rest, &["sus2", "sus4", "sus"]) {
+            if sus.is_some() {
+                return Err(unexpected());
+            }
+            sus = Some(t);
+            rest = r;
+        } else if let Some((t, r)) = strip_any(rest, &["add", "omit", "no"]) {
+            let (accidental, r) = strip_alteration(r).ok_or_else(unexpected)?;
+            let (number, r) = strip_number(r).ok_or_else(unexpected)?;
+            if t == "add" {
+                additions.push(chord_tone(accidental, number)?);
+            } else if accidental == 0 {
+                omissions.push(number);
+            } else {
+                return Err(unexpected());
+            }
+            rest = r;
+        } else if let Some((_, r)) = strip_any(rest, &["alt"]) {
+            alterations.extend_from_slice(&[MIN_9, AUG_9, AUG_11, MIN_13]);
+            omissions.push(5);
+            rest = r;
+        } else if let Some((_, r)) = strip_any(rest, &["6/9", "69"]) {
+            if extension.is_some() {
+                return Err(unexpected());
+            }
+            extension = Some(6);
+            additions.push(MAJ_9);
+            rest = r;
+        } else {
+            let (accidental, r) = strip_alteration(rest).ok_or_else(unexpected)?;
+            let (number, r) = strip_number(r).ok_or_else(unexpected)?;
+            if accidental != 0 {
+                alterations.push(chord_tone(accidental, number)?);
+            } else if extension.is_some() {
+                return Err(unexpected());
+            } else if [5, 6, 7, 9, 11, 13].contains(&number) {
+                extension = Some(number);
+            } else {
+                return Err(ParseChordError::InvalidNumber(number));
+            }
+            rest = r;
+        }
+    }
+
+    let conflict = |a: &str, b: &str| Err(ParseChordError::ConflictingTokens(a.into(), b.into()));
+    let delta = major_seventh.is_some_and(|t| t == "Δ" || t == "△");
+    match (extension, major_seventh, sus) {
+        (Some(5), _, _) if triad != Triad::Major => return conflict(quality, "5"),
+        (Some(5), Some(t), _) => return conflict(t, "5"),
+        (Some(6), Some(t), _) => return conflict(t, "6"),
+        (Some(6), _, _) if triad == Triad::HalfDiminished => return conflict(quality, "6"),
+        (_, _, Some(t)) if triad != Triad::Major => return conflict(quality, t),
+        _ => {}
+    }
+
+    let mut intervals = match triad {
+        Triad::Major => vec![MAJ_3, PER_5],
+        Triad::Minor => vec![MIN_3, PER_5],
+        Triad::Diminished => vec![MIN_3, DIM_5],
+        Triad::HalfDiminished => vec![MIN_3, DIM_5, MIN_7],
+        Triad::Augmented => vec![MAJ_3, AUG_5],
+    };
+    if extension.map_or(delta, |n| n >= 7) && triad != Triad::HalfDiminished {
+        intervals.push(match (major_seventh, triad) {
+            (Some(_), _) => MAJ_7,
+            (None, Triad::Diminished) => DIM_7,
+            (None, _) => MIN_7,
+        });
+    }
+    match extension {
+        Some(5) => intervals = vec![PER_5],
+        Some(6) => intervals.push(MAJ_6),
+        Some(9) => intervals.push(MAJ_9),
+        Some(11) => intervals.extend_from_slice(&[MAJ_9, PER_11]),
+        Some(13) if triad == Triad::Minor => intervals.extend_from_slice(&[MAJ_9, PER_11, MAJ_13]),
+        Some(13) => intervals.extend_from_slice(&[MAJ_9, MAJ_13]),
+        _ => {}
+    }
+    if let Some(t) = sus {
+        let suspension = if t == "sus2" { MAJ_2 } else { PER_4 };
+        match intervals.iter().position(|i| i.number() == 3) {
+            Some(ix) => intervals[ix] = suspension,
+            None => intervals.insert(0, suspension),
+        }
+    }
+    intervals.retain(|i| alterations.iter().all(|a| a.number() != i.number()));
+    intervals.extend(alterations);
+    intervals.extend(additions);
+    for number in omissions {
+        if !intervals.iter().any(|i| i.number() == number) {
+            return Err(ParseChordError::NothingToOmit(number));
+        }
+        intervals.retain(|i| i.number() != number);
+    }
+    Ok(intervals)
+}
+
+/// Splits off flats or sharps before a number, also written as `-` or `+` as in `C7+5`.
+fn strip_alteration(s: &str) -> Option<(i8, &str)> {
+    let sign = match s.chars().next() {
+        Some('+') => 1,
+        Some('-') => -1,
+        _ => return strip_accidental(s),
+    };
+    let rest = &s[1..];
+    if rest.starts_with(|c: char| c.is_ascii_digit()) {
+        Some((sign, rest))
+    } else {
+        None
+    }
+}
+
+fn strip_number(s: &str) -> Option<(u8, &str)> {
+    let end = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    s[..end].parse().ok().map(|n| (n, &s[end..]))
+}
+
+/// The major or perfect interval of `number` above the root.
+fn natural(number: u8) -> Option<SpelledInterval> {
+    SpelledInterval::new(IntervalQuality::Major, number)
+        .or_else(|| SpelledInterval::new(IntervalQuality::Perfect, number))
+}
+
+/// The chord tone `number`, raised or lowered by `accidental` semitones, e.g. `b9`.
+fn chord_tone(accidental: i8, number: u8) -> Result<SpelledInterval, ParseChordError> {
+    if ![2, 3, 4, 5, 6, 7, 9, 11, 13].contains(&number) {
+        return Err(ParseChordError::InvalidNumber(number));
+    }
+    let natural = natural(number).unwrap();
+    Ok(SpelledInterval::with_number(
+        number,
+        natural.interval() + Interval::new(accidental),
+    ))
+}
+
+/// `b`, `#` or nothing, depending on how `interval` differs from the major or perfect one.
+fn accidental_name(interval: SpelledInterval) -> String {
+    let natural = natural(interval.number()).unwrap();
+    let offset = interval.interval().semitones() - natural.interval().semitones();
+    let symbol = if offset < 0 { "b" } else { "#" };
+    symbol.repeat(offset.unsigned_abs() as usize)
+}
+
+/// Removes `interval` from `intervals`, returning whether it was there.
+fn take(intervals: &mut Vec<SpelledInterval>, interval: SpelledInterval) -> bool {
+    match intervals.iter().position(|&i| i == interval) {
+        Some(ix) => {
+            intervals.remove(ix);
+            true
+        }
+        None => false,
+    }
+}
+
+pub mod chord_constants {
+    use super::super::super::spelled_interval_constants::*;
+    use super::super::super::SpelledInterval;
+
+    pub const POWER: &[SpelledInterval] = &[PER_5];
+    pub const MAJOR: &[SpelledInterval] = &[MAJ_3, PER_5];
+    pub const MINOR: &[SpelledInterval] = &[MIN_3, PER_5];
+    pub const DIMINISHED: &[SpelledInterval] = &[MIN_3, DIM_5];
+    pub const AUGMENTED: &[SpelledInterval] = &[MAJ_3, AUG_5];
+    pub const SUS_2: &[SpelledInterval] = &[MAJ_2, PER_5];
+    pub const SUS_4: &[SpelledInterval] = &[PER_4, PER_5];
+    pub const MAJOR_6: &[SpelledInterval] = &[MAJ_3, PER_5, MAJ_6];
+    pub const MINOR_6: &[SpelledInterval] = &[MIN_3, PER_5, MAJ_6];
+    pub const SIX_NINE: &[SpelledInterval] = &[MAJ_3, PER_5, MAJ_6, MAJ_9];
+    pub const DOMINANT_7: &[SpelledInterval] = &[MAJ_3, PER_5, MIN_7];
+    pub const MAJOR_7: &[SpelledInterval] = &[MAJ_3, PER_5, MAJ_7];
+    pub const MINOR_7: &[SpelledInterval] = &[MIN_3, PER_5, MIN_7];
+    pub const MINOR_MAJOR_7: &[SpelledInterval] = &[MIN_3, PER_5, MAJ_7];
+    pub const HALF_DIMINISHED_7: &[SpelledInterval] = &[MIN_3, DIM_5, MIN_7];
+    pub const DIMINISHED_7: &[SpelledInterval] = &[MIN_3, DIM_5, DIM_7];
+    pub const AUGMENTED_7: &[SpelledInterval] = &[MAJ_3, AUG_5, MIN_7];
+    pub const AUGMENTED_MAJOR_7: &[SpelledInterval] = &[MAJ_3, AUG_5, MAJ_7];
+    pub const DOMINANT_7_SUS_4: &[SpelledInterval] = &[PER_4, PER_5, MIN_7];
+    pub const ADD_9: &[SpelledInterval] = &[MAJ_3, PER_5, MAJ_9];
+    pub const MINOR_ADD_9: &[SpelledInterval] = &[MIN_3, PER_5, MAJ_9];
+    pub const DOMINANT_9: &[SpelledInterval] = &[MAJ_3, PER_5, MIN_7, MAJ_9];
+    pub const MAJOR_9: &[SpelledInterval] = &[MAJ_3, PER_5, MAJ_7, MAJ_9];
+    pub const MINOR_9: &[SpelledInterval] = &[MIN_3, PER_5, MIN_7, MAJ_9];
+    pub const DOMINANT_11: &[SpelledInterval] = &[MAJ_3, PER_5, MIN_7, MAJ_9, PER_11];
+    pub const MINOR_11: &[SpelledInterval] = &[MIN_3, PER_5, MIN_7, MAJ_9, PER_11];
+    pub const DOMINANT_13: &[SpelledInterval] = &[MAJ_3, PER_5, MIN_7, MAJ_9, MAJ_13];
+    pub const MAJOR_13: &[SpelledInterval] = &[MAJ_3, PER_5, MAJ_7, MAJ_9, MAJ_13];
+    pub const MINOR_13: &[SpelledInterval] = &[MIN_3, PER_5, MIN_7, MAJ_9, PER_11, MAJ_13];
+
+    /// Known chord qualities by their lead sheet suffix, most common first.
+    pub const CHORD_SUFFIXES: [(&str, &[SpelledInterval]); 29] = [
+        ("", MAJOR),
+        ("m", MINOR),
+        ("7", DOMINANT_7),
+        ("maj7", MAJOR_7),
+        ("m7", MINOR_7),
+        ("°", DIMINISHED),
+        ("+", AUGMENTED),
+        ("ø7", HALF_DIMINISHED_7),
+        ("°7", DIMINISHED_7),
+        ("sus4", SUS_4),
+        ("sus2", SUS_2),
+        ("6", MAJOR_6),
+        ("m6", MINOR_6),
+        ("5", POWER),
+        ("7sus4", DOMINANT_7_SUS_4),
+        ("m(maj7)", MINOR_MAJOR_7),
+        ("+7", AUGMENTED_7),
+        ("+(maj7)", AUGMENTED_MAJOR_7),
+        ("add9", ADD_9),
+        ("m(add9)", MINOR_ADD_9),
+        ("6/9", SIX_NINE),
+        ("9", DOMINANT_9),
+        ("maj9", MAJOR_9),
+        ("m9", MINOR_9),
+        ("11", DOMINANT_11),
+        ("m11", MINOR_11),
+        ("13", DOMINANT_13),
+        ("maj13", MAJOR_13),
+        ("m13", MINOR_13),
+    ];
+}
+
+/******************************************************************************
+* TESTS
+******************************************************************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chord(s: &str) -> Chord {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn symbols_round_trip() {
+        let symbols = [
+            "C",
+            "Cm",
+            "C7",
+            "Cmaj7",
+            "Cm7",
+            "Cø7",
+            "C°7",
+            "C+",
+            "Csus4",
+            "C7sus4",
+            "C6",
+            "Cm6",
+            "C6/9",
+            "C9",
+            "C11",
+            "C13",
+            "C7(b9)",
+            "C7(#9)",
+            "C13(#11)",
+            "C7(b9,#11)",
+            "Cadd9",
+            "Cm(add9)",
+            "C/E",
+            "Bb7/D",
+            "F#m7",
+            "Ebm9",
+            "C5",
+            "Cmaj9",
+            "Cm(maj7)",
+            "C7(no5)",
+            "C9(no3)",
+        ];
+        for &s in symbols.iter() {
+            assert_eq!(chord(s).to_string(), s);
+        }
+    }
+
+    #[test]
+    fn alternative_spellings() {
+        let spellings = [
+            ("Cm7b5", "Cø7"),
+            ("Cdim7", "C°7"),
+            ("C69", "C6/9"),
+            ("CmMaj7", "Cm(maj7)"),
+            ("C7+5", "C+7"),
+            ("C7-9", "C7(b9)"),
+            ("Cadd-9", "C(b9)"),
+            ("C7omit5", "C7(no5)"),
+            ("Comit5", "C(no5)"),
+            ("C5sus4", "Csus4"),
+            ("C5sus2", "Csus2"),
+        ];
+        for &(s, canonical) in spellings.iter() {
+            assert_eq!(chord(s), chord(canonical), "{}", s);
+            assert_eq!(chord(s).to_string(), canonical);
+        }
+    }
+
+    #[test]
+    fn invalid_symbols() {
+        for &s in ["C7b#9", "C7#b9", "C7+", "C7-", "C7x", "C8"].iter() {
+            assert!(s.parse::<Chord>().is_err(), "{}", s);
+        }
+        assert_eq!("".parse::<Chord>(), Err(ParseChordError::Empty));
+    }
+}
@@ -0,0 +1,72 @@
+use super::super::{Note, PitchClassSet};
+use super::{Chord, ChordSymbol, ParseChordError};
+
+impl ChordSymbol {
+    /// The chord sounding lowest, which carries the bass.
+    pub fn lower(&self) -> &Chord {
+        match self {
+            ChordSymbol::Chord(chord) => chord,
+            ChordSymbol::Poly { lower, .. } => lower,
+        }
+    }
+
+    pub fn pitch_class_set(&self) -> PitchClassSet {
+        match self {
+            ChordSymbol::Chord(chord) => chord.pitch_class_set(),
+            ChordSymbol::Poly { upper, lower } => upper.pitch_class_set() | lower.pitch_class_set(),
+        }
+    }
+
+    /// A close voicing with the lower root in `octave` and any upper chord an octave above it.
+    pub fn notes(&self, octave: i8) -> Vec<Note> {
+        match self {
+            ChordSymbol::Chord(chord) => chord.notes(octave),
+            ChordSymbol::Poly { upper, lower } => {
+                let mut notes = lower.notes(octave);
+                notes.extend(upper.notes(octave + 1));
+                notes
+            }
+        }
+    }
+}
+
+impl From<Chord> for ChordSymbol {
+    fn from(chord: Chord) -> Self {
+        ChordSymbol::Chord(chord)
+    }
+}
+
+/// Prints polychords upper chord first, e.g. `D|C`.
+impl std::fmt::Display for ChordSymbol {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ChordSymbol::Chord(chord) => write!(f, "{}", chord),
+            ChordSymbol::Poly { upper, lower } => write!(f, "{}|{}", upper, lower),
+        }
+    }
+}
+
+impl std::str::FromStr for ChordSymbol {
+    type Err = ParseChordError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.find('|') {
+            None => Ok(ChordSymbol::Chord(s.parse()?)),
+            Some(ix) => {
+                if let Some(second) = s[ix + 1..].find('|') {
+                    let rest = &s[ix + 1 + second..];
+                    return Err(ParseChordError::UnexpectedToken(rest.to_string()));
+                }
+                Ok(ChordSymbol::Poly {
+                    upper: s[..ix].parse()?,
+                    lower: s[ix + 1..].parse()?,
+                })
+            }
+        }
+    }
+}
+
+/// Parses whitespace separated chord symbols, e.g. `Dm7 G7 Cmaj7`.
+pub fn parse_chord_symbols(s: &str) -> Result<Vec<ChordSymbol>, ParseChordError> {
+    s.split_whitespace().map(str::parse).collect()
+}
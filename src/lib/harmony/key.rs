@@ -0,0 +1,63 @@
+use super::super::{
+    Interval, Note, PitchClassSet, ScaleFamily, SpelledPitch, SpelledPitchClass, AEOLIAN,
+    CHROMATIC_COUNT, DIATONIC_COUNT, IONIAN,
+};
+use super::{Chord, Key};
+
+impl Key {
+    pub fn new(tonic: SpelledPitchClass, family: &ScaleFamily, mode: usize) -> Key {
+        let mut scale = [Interval::default(); DIATONIC_COUNT];
+        scale.copy_from_slice(&family.modes[mode]);
+        Key { tonic, scale }
+    }
+
+    pub fn major(tonic: SpelledPitchClass) -> Key {
+        Key::new(tonic, &ScaleFamily::major(), IONIAN)
+    }
+
+    /// The natural minor key.
+    pub fn minor(tonic: SpelledPitchClass) -> Key {
+        Key::new(tonic, &ScaleFamily::major(), AEOLIAN)
+    }
+
+    pub fn tonic(&self) -> SpelledPitchClass {
+        self.tonic
+    }
+
+    /// Intervals of each scale degree above the tonic.
+    pub fn scale(&self) -> &[Interval; DIATONIC_COUNT] {
+        &self.scale
+    }
+
+    pub fn spelled_scale(&self) -> Vec<SpelledPitchClass> {
+        self.tonic.spell_scale(&self.scale)
+    }
+
+    pub fn pitch_class_set(&self) -> PitchClassSet {
+        self.scale
+            .iter()
+            .map(|&i| self.tonic.pitch_class() + i)
+            .collect()
+    }
+
+    /// The scale rotated to start on `scale_degree`, as `ScaleFamily::chord_scale_0` does for modes.
+    pub fn chord_scale(&self, scale_degree: usize) -> [Interval; DIATONIC_COUNT] {
+        let offset = self.scale[scale_degree % DIATONIC_COUNT];
+        let mut chord_scale = self.scale;
+        for (i, interval) in chord_scale.iter_mut().enumerate() {
+            *interval = (self.scale[(scale_degree + i) % DIATONIC_COUNT] - offset)
+                .positive_less_than(CHROMATIC_COUNT as i8);
+        }
+        chord_scale
+    }
+
+    /// The chord of `size` stacked thirds on `scale_degree`.
+    pub fn diatonic_chord(&self, scale_degree: usize, size: usize) -> Chord {
+        let root = self.spelled_scale()[scale_degree % DIATONIC_COUNT];
+        Chord::stacked_thirds(root, &self.chord_scale(scale_degree), size)
+    }
+
+    pub fn spell(&self, note: Note) -> SpelledPitch {
+        SpelledPitch::in_scale(note, self.tonic, &self.scale)
+    }
+}
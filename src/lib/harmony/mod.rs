@@ -0,0 +1,164 @@
+mod chord;
+mod chord_symbol;
+mod key;
+mod modulation;
+mod progression;
+mod reharmoniser;
+mod roman_numeral;
+
+pub use chord::*;
+pub use chord_symbol::*;
+pub use roman_numeral::*;
+
+use super::{AlteredDegree, Interval, SpelledInterval, SpelledPitchClass, DIATONIC_COUNT};
+
+/// https://en.wikipedia.org/wiki/Chord_(music)
+///
+/// Intervals are measured from the root, so extensions are compound, e.g. a ninth rather than a second.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Chord {
+    root: SpelledPitchClass,
+    intervals: Vec<SpelledInterval>,
+    bass: Option<SpelledPitchClass>,
+}
+
+/// A chord as written on a lead sheet, including polychords of one chord stacked above another.
+///
+/// https://en.wikipedia.org/wiki/Polychord
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ChordSymbol {
+    Chord(Chord),
+    Poly { upper: Chord, lower: Chord },
+}
+
+/// https://en.wikipedia.org/wiki/Key_(music)
+///
+/// A tonic together with the heptatonic scale, e.g. a mode of a `ScaleFamily`, built on it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Key {
+    tonic: SpelledPitchClass,
+    scale: [Interval; DIATONIC_COUNT],
+}
+
+/// https://en.wikipedia.org/wiki/Modulation_(music)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ModulationKind {
+    /// Through a chord diatonic to both keys.
+    Pivot,
+    /// Through a chord of the new key sharing a tone with the old tonic triad, but foreign to the
+    /// old key.
+    CommonTone,
+    /// Through the dominant of the new key, raising or lowering tones of the old key.
+    Chromatic,
+    /// Straight to the new tonic, as between phrases.
+    Direct,
+}
+
+/// The chords leading from one key to another, each on a scale degree of the key it is heard in.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Modulation {
+    from: Key,
+    to: Key,
+    kind: ModulationKind,
+    steps: Vec<(Key, usize)>,
+}
+
+/// https://en.wikipedia.org/wiki/Roman_numeral_analysis
+///
+/// Degrees are relative to the major scale on the tonic, so the subtonic of a minor key is `bVII`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RomanNumeral {
+    degree: AlteredDegree,
+    triad: TriadQuality,
+    seventh: Option<SpelledInterval>,
+    inversion: u8,
+    secondary: Option<Box<RomanNumeral>>,
+}
+
+/// https://en.wikipedia.org/wiki/Triad_(music)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TriadQuality {
+    Major,
+    Minor,
+    Diminished,
+    Augmented,
+}
+
+/// https://en.wikipedia.org/wiki/Function_(music)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum HarmonicFunction {
+    Tonic,
+    Predominant,
+    Dominant,
+}
+
+/// https://en.wikipedia.org/wiki/Cadence
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Cadence {
+    /// `V I`
+    Authentic,
+    /// `IV I`
+    Plagal,
+    /// Ending on `V`.
+    Half,
+    /// `V vi`
+    Deceptive,
+}
+
+/// A Markov chain over the scale degrees of a mode, weighted to move from tonic to predominant to
+/// dominant and back, closing each phrase with a cadence.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProgressionModel {
+    mode: usize,
+    transitions: [[f64; DIATONIC_COUNT]; DIATONIC_COUNT],
+    cadences: [f64; CADENCE_COUNT],
+}
+
+const CADENCE_COUNT: usize = 4;
+
+/// https://en.wikipedia.org/wiki/Reharmonization
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Reharmonisation {
+    /// Replaces a dominant seventh with the one a tritone away, e.g. `G7` with `Db7`.
+    TritoneSubstitution,
+    /// Precedes a chord with its dominant seventh.
+    SecondaryDominant,
+    /// Precedes a chord with its `ii7 V7`.
+    TwoFive,
+    /// Connects chords a whole step apart with the diminished seventh between them.
+    DiminishedPassing,
+    /// Replaces a diatonic chord with the one on the same degree of a parallel mode, e.g. `IV` with
+    /// `iv`.
+    ModalInterchange,
+    /// Replaces `V` before the tonic with `bVII7`.
+    BackdoorDominant,
+}
+
+/// Reharmonises progressions in a key, applying each enabled reharmonisation at random where it
+/// fits. The higher the dissonance, the more often, with the more colourful ones needing more.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Reharmoniser {
+    enabled: Vec<Reharmonisation>,
+    dissonance: f64,
+    parallel_scales: Vec<[Interval; DIATONIC_COUNT]>,
+}
+
+/// Splits off the first of `prefixes` that `s` starts with.
+fn strip_any<'a>(s: &'a str, prefixes: &[&'static str]) -> Option<(&'static str, &'a str)> {
+    prefixes
+        .iter()
+        .find(|p| s.starts_with(*p))
+        .map(|&p| (p, &s[p.len()..]))
+}
+
+/// Splits off leading flats or sharps, counted in semitones, or `None` where both are mixed.
+fn strip_accidental(s: &str) -> Option<(i8, &str)> {
+    let rest = s.trim_start_matches(['b', '♭']);
+    let flats = s[..s.len() - rest.len()].chars().count() as i8;
+    let sharps_rest = rest.trim_start_matches(['#', '♯']);
+    let sharps = rest[..rest.len() - sharps_rest.len()].chars().count() as i8;
+    if flats > 0 && sharps > 0 {
+        return None;
+    }
+    Some((sharps - flats, sharps_rest))
+}
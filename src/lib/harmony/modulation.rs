@@ -0,0 +1,223 @@
+use super::super::{PitchClassSet, DIATONIC_COUNT};
+use super::{Chord, Key, Modulation, ModulationKind};
+
+const TONIC: usize = 0;
+const SUPERTONIC: usize = 1;
+const SUBDOMINANT: usize = 3;
+const DOMINANT: usize = 4;
+
+impl ModulationKind {
+    pub const ALL: [ModulationKind; 4] = [
+        ModulationKind::Pivot,
+        ModulationKind::CommonTone,
+        ModulationKind::Chromatic,
+        ModulationKind::Direct,
+    ];
+}
+
+impl std::fmt::Display for ModulationKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(match self {
+            ModulationKind::Pivot => "pivot chord",
+            ModulationKind::CommonTone => "common tone",
+            ModulationKind::Chromatic => "chromatic",
+            ModulationKind::Direct => "direct",
+        })
+    }
+}
+
+impl Key {
+    /// Pairs of scale degrees, in `self` and in `other`, whose chords of `size` stacked thirds are
+    /// the same.
+    pub fn pivot_chords(&self, other: &Key, size: usize) -> Vec<(usize, usize)> {
+        let mut pivots = Vec::new();
+        for from in 0..DIATONIC_COUNT {
+            let chord = self.diatonic_chord(from, size).pitch_class_set();
+            for to in 0..DIATONIC_COUNT {
+                if other.diatonic_chord(to, size).pitch_class_set() == chord {
+                    pivots.push((from, to));
+                }
+            }
+        }
+        pivots
+    }
+
+    /// The pitch classes both keys share.
+    pub fn common_tones(&self, other: &Key) -> PitchClassSet {
+        self.pitch_class_set() & other.pitch_class_set()
+    }
+}
+
+impl Modulation {
+    /// Plans a modulation of `kind` by triads, ending on the new tonic, or `None` if the keys
+    /// allow no such modulation.
+    pub fn plan(from: &Key, to: &Key, kind: ModulationKind) -> Option<Modulation> {
+        let triad = |key: &Key, degree| key.diatonic_chord(degree, 3).pitch_class_set();
+        let steps = match kind {
+            ModulationKind::Pivot => {
+                // Pivots heard as predominants of the new key lead on most smoothly.
+                let (_, pivot) = from
+                    .pivot_chords(to, 3)
+                    .into_iter()
+                    .filter(|&(_, to_degree)| to_degree != TONIC)
+                    .min_by_key(|&(_, to_degree)| match to_degree {
+                        SUPERTONIC | SUBDOMINANT => 0,
+                        DOMINANT => 1,
+                        _ => 2,
+                    })?;
+                approach(to, pivot)
+            }
+            ModulationKind::CommonTone => {
+                let tonic = triad(from, TONIC);
+                let degree = [TONIC, DOMINANT, SUBDOMINANT, SUPERTONIC]
+                    .iter()
+                    .copied()
+                    .find(|&degree| {
+                        let chord = triad(to, degree);
+                        !chord.is_subset(from.pitch_class_set()) && !(chord & tonic).is_empty()
+                    })?;
+                approach(to, degree)
+            }
+            ModulationKind::Chromatic => {
+                if triad(to, DOMINANT).is_subset(from.pitch_class_set()) {
+                    return None;
+                }
+                approach(to, DOMINANT)
+            }
+            ModulationKind::Direct => vec![(*to, TONIC)],
+        };
+        Some(Modulation {
+            from: *from,
+            to: *to,
+            kind,
+            steps,
+        })
+    }
+
+    /// The first of pivot, common tone and chromatic modulations the keys allow, or else a direct
+    /// one.
+    pub fn smoothest(from: &Key, to: &Key) -> Modulation {
+        ModulationKind::ALL
+            .iter()
+            .find_map(|&kind| Modulation::plan(from, to, kind))
+            .unwrap()
+    }
+
+    pub fn from(&self) -> &Key {
+        &self.from
+    }
+
+    pub fn to(&self) -> &Key {
+        &self.to
+    }
+
+    pub fn kind(&self) -> ModulationKind {
+        self.kind
+    }
+
+    /// Each transitional chord as a scale degree of the key it belongs to, ending on the new tonic.
+    pub fn steps(&self) -> &[(Key, usize)] {
+        &self.steps
+    }
+
+    /// The steps realised as chords of `size` stacked thirds.
+    pub fn chords(&self, size: usize) -> Vec<Chord> {
+        self.steps
+            .iter()
+            .map(|(key, degree)| key.diatonic_chord(*degree, size))
+            .collect()
+    }
+}
+
+/// `degree` of `key`, followed by the dominant and tonic unless already there.
+fn approach(key: &Key, degree: usize) -> Vec<(Key, usize)> {
+    let mut steps = vec![(*key, degree)];
+    if degree != DOMINANT && degree != TONIC {
+        steps.push((*key, DOMINANT));
+    }
+    if degree != TONIC {
+        steps.push((*key, TONIC));
+    }
+    steps
+}
+
+/******************************************************************************
+* TESTS
+******************************************************************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn major(tonic: &str) -> Key {
+        Key::major(tonic.parse().unwrap())
+    }
+
+    fn chords(from: &str, to: &str, kind: ModulationKind) -> Option<Vec<String>> {
+        let modulation = Modulation::plan(&major(from), &major(to), kind)?;
+        assert_eq!(modulation.kind(), kind);
+        assert_eq!(modulation.steps().last(), Some(&(major(to), TONIC)));
+        Some(modulation.chords(3).iter().map(Chord::to_string).collect())
+    }
+
+    #[test]
+    fn pivot_chord_modulations() {
+        assert_eq!(
+            Modulation::plan(&major("C"), &major("G"), ModulationKind::Pivot)
+                .unwrap()
+                .steps(),
+            [
+                (major("G"), SUBDOMINANT),
+                (major("G"), DOMINANT),
+                (major("G"), TONIC)
+            ]
+        );
+        assert_eq!(
+            chords("C", "G", ModulationKind::Pivot),
+            Some(vec!["C".into(), "D".into(), "G".into()])
+        );
+        assert_eq!(
+            chords("C", "F", ModulationKind::Pivot),
+            Some(vec!["C".into(), "F".into()])
+        );
+        assert_eq!(chords("C", "F#", ModulationKind::Pivot), None);
+    }
+
+    #[test]
+    fn common_tone_modulations() {
+        assert_eq!(
+            chords("C", "E", ModulationKind::CommonTone),
+            Some(vec!["E".into()])
+        );
+        assert_eq!(
+            chords("C", "Ab", ModulationKind::CommonTone),
+            Some(vec!["Ab".into()])
+        );
+        assert_eq!(chords("C", "F#", ModulationKind::CommonTone), None);
+    }
+
+    #[test]
+    fn chromatic_and_direct_modulations() {
+        assert_eq!(
+            chords("C", "Ab", ModulationKind::Chromatic),
+            Some(vec!["Eb".into(), "Ab".into()])
+        );
+        assert_eq!(chords("C", "F", ModulationKind::Chromatic), None);
+        assert_eq!(
+            chords("C", "D", ModulationKind::Direct),
+            Some(vec!["D".into()])
+        );
+    }
+
+    #[test]
+    fn smoothest_modulations() {
+        let kind = |from, to| Modulation::smoothest(&major(from), &major(to)).kind();
+        assert_eq!(kind("C", "G"), ModulationKind::Pivot);
+        assert_eq!(kind("C", "Ab"), ModulationKind::CommonTone);
+        assert_eq!(kind("C", "F#"), ModulationKind::Chromatic);
+        assert_eq!(major("C").pivot_chords(&major("F#"), 3), []);
+        let common = major("C").common_tones(&major("G"));
+        assert_eq!(common.len(), 6);
+        assert!(!common.contains("F".parse().unwrap()));
+    }
+}
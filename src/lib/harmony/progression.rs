@@ -0,0 +1,204 @@
+use rand::{seq::SliceRandom, Rng};
+
+use super::super::interval_constants::{AUG_5, DIM_5, MAJ_7};
+use super::super::{Degree, ScaleFamily, DIATONIC_COUNT, FIFTH, IONIAN, SEVENTH};
+use super::{Cadence, HarmonicFunction, ProgressionModel, CADENCE_COUNT};
+
+const TONIC: Degree = Degree::new(0);
+const SUBDOMINANT: Degree = Degree::new(3);
+const DOMINANT: Degree = Degree::new(4);
+const SUBMEDIANT: Degree = Degree::new(5);
+
+impl HarmonicFunction {
+    pub const ALL: [HarmonicFunction; 3] = [
+        HarmonicFunction::Tonic,
+        HarmonicFunction::Predominant,
+        HarmonicFunction::Dominant,
+    ];
+
+    /// `I`, `iii` and `vi` are tonic, `ii` and `IV` predominant, `V` and `vii°` dominant.
+    pub fn of(degree: Degree) -> HarmonicFunction {
+        match degree.index().rem_euclid(DIATONIC_COUNT as i8) {
+            0 | 2 | 5 => HarmonicFunction::Tonic,
+            1 | 3 => HarmonicFunction::Predominant,
+            _ => HarmonicFunction::Dominant,
+        }
+    }
+
+    /// How strongly a chord of this function leads to one of `next`, before the mode has its say.
+    fn weight_to(self, next: HarmonicFunction) -> f64 {
+        match (self, next) {
+            (HarmonicFunction::Tonic, HarmonicFunction::Tonic) => 1.,
+            (HarmonicFunction::Tonic, HarmonicFunction::Predominant) => 2.,
+            (HarmonicFunction::Tonic, HarmonicFunction::Dominant) => 1.5,
+            (HarmonicFunction::Predominant, HarmonicFunction::Tonic) => 0.5,
+            (HarmonicFunction::Predominant, HarmonicFunction::Predominant) => 1.,
+            (HarmonicFunction::Predominant, HarmonicFunction::Dominant) => 3.,
+            (HarmonicFunction::Dominant, HarmonicFunction::Tonic) => 3.,
+            (HarmonicFunction::Dominant, HarmonicFunction::Predominant) => 0.2,
+            (HarmonicFunction::Dominant, HarmonicFunction::Dominant) => 0.5,
+        }
+    }
+}
+
+impl std::fmt::Display for HarmonicFunction {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(match self {
+            HarmonicFunction::Tonic => "T",
+            HarmonicFunction::Predominant => "PD",
+            HarmonicFunction::Dominant => "D",
+        })
+    }
+}
+
+impl Cadence {
+    pub const ALL: [Cadence; CADENCE_COUNT] = [
+        Cadence::Authentic,
+        Cadence::Plagal,
+        Cadence::Half,
+        Cadence::Deceptive,
+    ];
+
+    /// The degrees ending a phrase with this cadence.
+    pub fn degrees(self) -> &'static [Degree] {
+        match self {
+            Cadence::Authentic => &[DOMINANT, TONIC],
+            Cadence::Plagal => &[SUBDOMINANT, TONIC],
+            Cadence::Half => &[DOMINANT],
+            Cadence::Deceptive => &[DOMINANT, SUBMEDIANT],
+        }
+    }
+}
+
+impl std::fmt::Display for Cadence {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(match self {
+            Cadence::Authentic => "authentic",
+            Cadence::Plagal => "plagal",
+            Cadence::Half => "half",
+            Cadence::Deceptive => "deceptive",
+        })
+    }
+}
+
+impl ProgressionModel {
+    /// Weighs transitions by harmonic function, favouring root motion down a fifth and shunning
+    /// diminished and augmented triads. Dominants lacking a leading tone in `mode` are weakened.
+    pub fn new(family: &ScaleFamily, mode: usize) -> ProgressionModel {
+        let has_leading_tone = family.modes[mode][SEVENTH] == MAJ_7;
+        let mut transitions = [[0.; DIATONIC_COUNT]; DIATONIC_COUNT];
+        for (from, row) in transitions.iter_mut().enumerate() {
+            let from_function = HarmonicFunction::of(Degree::new(from as i8));
+            for (to, weight) in row.iter_mut().enumerate() {
+                if from == to {
+                    continue;
+                }
+                let to_function = HarmonicFunction::of(Degree::new(to as i8));
+                *weight = from_function.weight_to(to_function);
+                if (to + DIATONIC_COUNT - from) % DIATONIC_COUNT == 3 {
+                    *weight *= 1.5;
+                }
+                let fifth = family.chord_scale_0(mode, to)[FIFTH];
+                if fifth == DIM_5 || fifth == AUG_5 {
+                    *weight *= 0.2;
+                }
+                if to_function == HarmonicFunction::Dominant && !has_leading_tone {
+                    *weight *= 0.5;
+                }
+            }
+        }
+        ProgressionModel {
+            mode,
+            transitions,
+            cadences: [3., 1., 1.5, 1.],
+        }
+    }
+
+    pub fn with_transition(&self, from: Degree, to: Degree, weight: f64) -> ProgressionModel {
+        assert!(weight >= 0.);
+        let mut model = self.clone();
+        model.transitions[from.index() as usize][to.index() as usize] = weight;
+        model
+    }
+
+    pub fn with_cadence_weight(&self, cadence: Cadence, weight: f64) -> ProgressionModel {
+        assert!(weight >= 0.);
+        let mut model = self.clone();
+        model.cadences[cadence as usize] = weight;
+        model
+    }
+
+    pub fn mode(&self) -> usize {
+        self.mode
+    }
+
+    pub fn transition(&self, from: Degree, to: Degree) -> f64 {
+        self.transitions[from.index() as usize][to.index() as usize]
+    }
+
+    pub fn cadence_weight(&self, cadence: Cadence) -> f64 {
+        self.cadences[cadence as usize]
+    }
+
+    /// The degree following `from`, or the tonic if `from` leads nowhere.
+    pub fn next<R: Rng>(&self, from: Degree, rng: &mut R) -> Degree {
+        self.choose(rng, |to| self.transition(from, to))
+            .unwrap_or(TONIC)
+    }
+
+    pub fn cadence<R: Rng>(&self, rng: &mut R) -> Cadence {
+        *Cadence::ALL
+            .choose_weighted(rng, |&cadence| self.cadence_weight(cadence))
+            .unwrap_or(&Cadence::Authentic)
+    }
+
+    /// `length` degrees starting on the tonic and ending in a cadence picked by weight.
+    pub fn phrase<R: Rng>(&self, length: usize, rng: &mut R) -> Vec<Degree> {
+        let cadence = self.cadence(rng);
+        self.phrase_with_cadence(length, cadence, rng)
+    }
+
+    /// `length` degrees starting on the tonic and ending in `cadence`, of which only the last
+    /// degrees are kept when the phrase is too short to hold both.
+    pub fn phrase_with_cadence<R: Rng>(
+        &self,
+        length: usize,
+        cadence: Cadence,
+        rng: &mut R,
+    ) -> Vec<Degree> {
+        let ending = cadence.degrees();
+        if length <= ending.len() {
+            return ending[ending.len() - length..].to_vec();
+        }
+
+        let mut degrees = vec![TONIC];
+        let free = length - ending.len();
+        while degrees.len() < free {
+            let from = *degrees.last().unwrap();
+            let next = if degrees.len() + 1 == free {
+                // Leads into the cadence, e.g. with `ii` before `V I`.
+                self.choose(rng, |to| {
+                    self.transition(from, to) * self.transition(to, ending[0])
+                })
+                .unwrap_or_else(|| self.next(from, rng))
+            } else {
+                self.next(from, rng)
+            };
+            degrees.push(next);
+        }
+        degrees.extend_from_slice(ending);
+        degrees
+    }
+
+    fn choose<R: Rng, F: Fn(Degree) -> f64>(&self, rng: &mut R, weight: F) -> Option<Degree> {
+        let degrees: Vec<Degree> = (0..DIATONIC_COUNT as i8).map(Degree::new).collect();
+        degrees.choose_weighted(rng, |&to| weight(to)).ok().copied()
+    }
+}
+
+/// The default model of the major scale.
+impl Default for ProgressionModel {
+    fn default() -> Self {
+        ProgressionModel::new(&ScaleFamily::major(), IONIAN)
+    }
+}
@@ -0,0 +1,316 @@
+use rand::{seq::SliceRandom, Rng};
+
+use super::super::spelled_interval_constants::{AUG_1, DIM_5, MAJ_2, MAJ_3, MIN_3, MIN_7, PER_5};
+use super::super::{Interval, ScaleFamily, SpelledPitchClass, AEOLIAN, DIATONIC_COUNT};
+use super::chord_constants::{DIMINISHED_7, DOMINANT_7, HALF_DIMINISHED_7, MINOR_7};
+use super::{Chord, Key, Reharmonisation, Reharmoniser};
+
+impl Reharmonisation {
+    pub const ALL: [Reharmonisation; 6] = [
+        Reharmonisation::ModalInterchange,
+        Reharmonisation::BackdoorDominant,
+        Reharmonisation::SecondaryDominant,
+        Reharmonisation::TwoFive,
+        Reharmonisation::DiminishedPassing,
+        Reharmonisation::TritoneSubstitution,
+    ];
+
+    /// How far from the original harmony the reharmonisation strays, from 0 to 1.
+    pub fn tension(self) -> f64 {
+        match self {
+            Reharmonisation::SecondaryDominant => 0.2,
+            Reharmonisation::TwoFive => 0.3,
+            Reharmonisation::ModalInterchange => 0.4,
+            Reharmonisation::BackdoorDominant => 0.5,
+            Reharmonisation::DiminishedPassing => 0.6,
+            Reharmonisation::TritoneSubstitution => 0.7,
+        }
+    }
+}
+
+impl std::fmt::Display for Reharmonisation {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(match self {
+            Reharmonisation::TritoneSubstitution => "tritone substitution",
+            Reharmonisation::SecondaryDominant => "secondary dominant",
+            Reharmonisation::TwoFive => "ii-V",
+            Reharmonisation::DiminishedPassing => "diminished passing chord",
+            Reharmonisation::ModalInterchange => "modal interchange",
+            Reharmonisation::BackdoorDominant => "backdoor dominant",
+        })
+    }
+}
+
+impl Reharmoniser {
+    /// All reharmonisations enabled at `dissonance`, from 0 to 1, borrowing from the parallel
+    /// minor.
+    pub fn new(dissonance: f64) -> Reharmoniser {
+        Reharmoniser {
+            enabled: Reharmonisation::ALL.to_vec(),
+            dissonance: dissonance.clamp(0., 1.),
+            parallel_scales: Vec::new(),
+        }
+        .with_parallel_mode(&ScaleFamily::major(), AEOLIAN)
+    }
+
+    pub fn with_enabled(&self, reharmonisation: Reharmonisation, enabled: bool) -> Reharmoniser {
+        let mut reharmoniser = self.clone();
+        reharmoniser.enabled.retain(|&r| r != reharmonisation);
+        if enabled {
+            reharmoniser.enabled.push(reharmonisation);
+        }
+        reharmoniser
+    }
+
+    pub fn with_dissonance(&self, dissonance: f64) -> Reharmoniser {
+        Reharmoniser {
+            dissonance: dissonance.clamp(0., 1.),
+            ..self.clone()
+        }
+    }
+
+    /// Also borrows chords from `mode` of `family` on the same tonic.
+    pub fn with_parallel_mode(&self, family: &ScaleFamily, mode: usize) -> Reharmoniser {
+        let mut scale = [Interval::default(); DIATONIC_COUNT];
+        scale.copy_from_slice(&family.modes[mode]);
+        let mut reharmoniser = self.clone();
+        reharmoniser.parallel_scales.push(scale);
+        reharmoniser
+    }
+
+    pub fn is_enabled(&self, reharmonisation: Reharmonisation) -> bool {
+        self.enabled.contains(&reharmonisation)
+    }
+
+    pub fn dissonance(&self) -> f64 {
+        self.dissonance
+    }
+
+    /// How likely `reharmonisation` is applied wherever it fits.
+    pub fn probability(&self, reharmonisation: Reharmonisation) -> f64 {
+        if !self.is_enabled(reharmonisation) {
+            return 0.;
+        }
+        (1.5 * self.dissonance - reharmonisation.tension()).clamp(0., 1.)
+    }
+
+    /// Applies the enabled reharmonisations one after the other, in the order of
+    /// `Reharmonisation::ALL`, so e.g. inserted dominants may get substituted by tritone.
+    pub fn reharmonise<R: Rng>(&self, chords: &[Chord], key: &Key, rng: &mut R) -> Vec<Chord> {
+        let mut chords = chords.to_vec();
+        for &reharmonisation in Reharmonisation::ALL.iter() {
+            let p = self.probability(reharmonisation);
+            if p > 0. {
+                chords = self.apply(reharmonisation, &chords, key, p, rng);
+            }
+        }
+        chords
+    }
+
+    /// Applies `reharmonisation` with probability `p` at each place it fits.
+    pub fn apply<R: Rng>(
+        &self,
+        reharmonisation: Reharmonisation,
+        chords: &[Chord],
+        key: &Key,
+        p: f64,
+        rng: &mut R,
+    ) -> Vec<Chord> {
+        let mut result: Vec<Chord> = Vec::with_capacity(chords.len());
+        for (i, chord) in chords.iter().enumerate() {
+            let previous = result.last();
+            let next = chords.get(i + 1);
+            match reharmonisation {
+                Reharmonisation::TritoneSubstitution if is_dominant_7(chord) && rng.gen_bool(p) => {
+                    let root = chord.root().pitch_class() + DIM_5.interval();
+                    result.push(Chord::new(
+                        SpelledPitchClass::common(root),
+                        chord.intervals(),
+                    ));
+                    continue;
+                }
+                Reharmonisation::ModalInterchange
+                    if chord.root() != key.tonic() && rng.gen_bool(p) =>
+                {
+                    if let Some(borrowed) = self.borrow(chord, key, rng) {
+                        result.push(borrowed);
+                        continue;
+                    }
+                }
+                Reharmonisation::BackdoorDominant
+                    if is_dominant_of(chord, key.tonic())
+                        && next.is_some_and(|next| next.root() == key.tonic())
+                        && rng.gen_bool(p) =>
+                {
+                    result.push(Chord::new(key.tonic() + MIN_7, DOMINANT_7));
+                    continue;
+                }
+                Reharmonisation::SecondaryDominant
+                    if i > 0
+                        && chord.root() != key.tonic()
+                        && is_consonant_triad(chord)
+                        && !previous.is_some_and(|p| is_dominant_of(p, chord.root()))
+                        && rng.gen_bool(p) =>
+                {
+                    result.push(Chord::new(chord.root() + PER_5, DOMINANT_7));
+                }
+                Reharmonisation::TwoFive
+                    if i > 0
+                        && is_consonant_triad(chord)
+                        && !previous.is_some_and(|p| is_dominant_of(p, chord.root()))
+                        && rng.gen_bool(p) =>
+                {
+                    let two = match chord.third() {
+                        Some(MIN_3) => HALF_DIMINISHED_7,
+                        _ => MINOR_7,
+                    };
+                    result.push(Chord::new(chord.root() + MAJ_2, two));
+                    result.push(Chord::new(chord.root() + PER_5, DOMINANT_7));
+                }
+                Reharmonisation::DiminishedPassing => {
+                    if let Some(previous) = previous {
+                        let step = chord.root().pitch_class() - previous.root().pitch_class();
+                        if step == MAJ_2.interval() && rng.gen_bool(p) {
+                            result.push(Chord::new(previous.root() + AUG_1, DIMINISHED_7));
+                        }
+                    }
+                }
+                _ => {}
+            }
+            result.push(chord.clone());
+        }
+        result
+    }
+
+    /// The chord on the same degree of a random parallel scale with the same number of tones, if
+    /// `chord` is diatonic and any of them differs.
+    fn borrow<R: Rng>(&self, chord: &Chord, key: &Key, rng: &mut R) -> Option<Chord> {
+        if !chord.pitch_class_set().is_subset(key.pitch_class_set()) {
+            return None;
+        }
+        let degree = key
+            .spelled_scale()
+            .iter()
+            .position(|&pc| pc == chord.root())?;
+        let size = (chord.intervals().len() + 1).min(DIATONIC_COUNT);
+        let options: Vec<Chord> = self
+            .parallel_scales
+            .iter()
+            .map(|&scale| {
+                Key {
+                    tonic: key.tonic(),
+                    scale,
+                }
+                .diatonic_chord(degree, size)
+            })
+            .filter(|borrowed| borrowed.pitch_class_set() != chord.pitch_class_set())
+            .collect();
+        options.choose(rng).cloned()
+    }
+}
+
+/// At dissonance 0.5.
+impl Default for Reharmoniser {
+    fn default() -> Self {
+        Reharmoniser::new(0.5)
+    }
+}
+
+fn is_dominant_7(chord: &Chord) -> bool {
+    chord.third() == Some(MAJ_3) && chord.seventh() == Some(MIN_7)
+}
+
+/// Whether `chord` is a major chord a fifth above `target`, seventh or not.
+fn is_dominant_of(chord: &Chord, target: SpelledPitchClass) -> bool {
+    chord.root() == target + PER_5
+        && chord.third() == Some(MAJ_3)
+        && chord.seventh().is_none_or(|seventh| seventh == MIN_7)
+}
+
+fn is_consonant_triad(chord: &Chord) -> bool {
+    chord.fifth() == Some(PER_5) && chord.third().is_some()
+}
+
+/******************************************************************************
+* TESTS
+******************************************************************************/
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::SmallRng, SeedableRng};
+
+    use super::*;
+
+    /// `chords` in C major with `reharmonisation` applied wherever it fits.
+    fn apply(reharmonisation: Reharmonisation, chords: &str) -> String {
+        let chords: Vec<Chord> = chords
+            .split_whitespace()
+            .map(|chord| chord.parse().unwrap())
+            .collect();
+        let key = Key::major("C".parse().unwrap());
+        let mut rng = SmallRng::seed_from_u64(0);
+        let result = Reharmoniser::default().apply(reharmonisation, &chords, &key, 1., &mut rng);
+        let result: Vec<String> = result.iter().map(Chord::to_string).collect();
+        result.join(" ")
+    }
+
+    #[test]
+    fn each_reharmonisation() {
+        assert_eq!(
+            apply(Reharmonisation::TritoneSubstitution, "Dm7 G7 C"),
+            "Dm7 Db7 C"
+        );
+        assert_eq!(
+            apply(Reharmonisation::BackdoorDominant, "Dm G C G"),
+            "Dm Bb7 C G"
+        );
+        assert_eq!(
+            apply(Reharmonisation::SecondaryDominant, "C F G C"),
+            "C F D7 G C"
+        );
+        assert_eq!(apply(Reharmonisation::TwoFive, "C Am"), "C Bø7 E7 Am");
+        assert_eq!(
+            apply(Reharmonisation::DiminishedPassing, "C Dm F"),
+            "C C#°7 Dm F"
+        );
+        assert_eq!(
+            apply(Reharmonisation::ModalInterchange, "C F G7 C"),
+            "C Fm Gm7 C"
+        );
+    }
+
+    #[test]
+    fn reharmonisations_only_where_they_fit() {
+        assert_eq!(apply(Reharmonisation::TritoneSubstitution, "G C"), "G C");
+        assert_eq!(apply(Reharmonisation::BackdoorDominant, "G7 Am"), "G7 Am");
+        assert_eq!(apply(Reharmonisation::SecondaryDominant, "G C F"), "G C F");
+        assert_eq!(apply(Reharmonisation::DiminishedPassing, "C Em"), "C Em");
+        assert_eq!(apply(Reharmonisation::ModalInterchange, "C Bb"), "C Bb");
+    }
+
+    #[test]
+    fn probabilities_by_dissonance() {
+        let reharmoniser = Reharmoniser::new(0.);
+        for &reharmonisation in Reharmonisation::ALL.iter() {
+            assert_eq!(reharmoniser.probability(reharmonisation), 0.);
+        }
+        let reharmoniser =
+            Reharmoniser::new(1.).with_enabled(Reharmonisation::TritoneSubstitution, false);
+        assert_eq!(
+            reharmoniser.probability(Reharmonisation::TritoneSubstitution),
+            0.
+        );
+        assert_eq!(
+            reharmoniser.probability(Reharmonisation::SecondaryDominant),
+            1.
+        );
+
+        let chords = vec!["C".parse::<Chord>().unwrap(), "G7".parse().unwrap()];
+        let key = Key::major("C".parse().unwrap());
+        let mut rng = SmallRng::seed_from_u64(0);
+        assert_eq!(
+            Reharmoniser::new(0.).reharmonise(&chords, &key, &mut rng),
+            chords
+        );
+    }
+}
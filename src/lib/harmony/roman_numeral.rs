@@ -0,0 +1,391 @@
+use super::super::scale_family_constants::MAJOR_FAMILY_SPELLED;
+use super::super::spelled_interval_constants::*;
+use super::super::{
+    Accidental, AlteredDegree, Degree, ParseDegreeError, SpelledInterval, SpelledPitchClass,
+    CHROMATIC_COUNT, DIATONIC_COUNT,
+};
+use super::{strip_accidental, strip_any, Chord, Key, RomanNumeral, TriadQuality};
+
+/******************************************************************************
+* ERRORS
+******************************************************************************/
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParseRomanNumeralError {
+    Empty,
+    InvalidDegree(ParseDegreeError),
+    MixedCase(String),
+    BeyondSeventh(String),
+    UnexpectedToken(String),
+}
+
+impl std::fmt::Display for ParseRomanNumeralError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ParseRomanNumeralError::Empty => write!(f, "expected a roman numeral, found nothing"),
+            ParseRomanNumeralError::InvalidDegree(e) => write!(f, "{}", e),
+            ParseRomanNumeralError::MixedCase(s) => write!(
+                f,
+                "\"{}\" mixes cases, use upper case for major and lower case for minor",
+                s
+            ),
+            ParseRomanNumeralError::BeyondSeventh(s) => {
+                write!(f, "\"{}\" is beyond the seventh degree", s)
+            }
+            ParseRomanNumeralError::UnexpectedToken(s) => {
+                write!(f, "unexpected \"{}\" in roman numeral", s)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseRomanNumeralError {}
+
+/******************************************************************************
+* IMPLS
+******************************************************************************/
+
+impl TriadQuality {
+    /// The third and fifth above the root.
+    pub fn intervals(self) -> [SpelledInterval; 2] {
+        match self {
+            TriadQuality::Major => [MAJ_3, PER_5],
+            TriadQuality::Minor => [MIN_3, PER_5],
+            TriadQuality::Diminished => [MIN_3, DIM_5],
+            TriadQuality::Augmented => [MAJ_3, AUG_5],
+        }
+    }
+
+    pub fn from_intervals(third: SpelledInterval, fifth: SpelledInterval) -> Option<TriadQuality> {
+        match (third, fifth) {
+            (MAJ_3, PER_5) => Some(TriadQuality::Major),
+            (MIN_3, PER_5) => Some(TriadQuality::Minor),
+            (MIN_3, DIM_5) => Some(TriadQuality::Diminished),
+            (MAJ_3, AUG_5) => Some(TriadQuality::Augmented),
+            _ => None,
+        }
+    }
+}
+
+impl RomanNumeral {
+    pub fn new(degree: AlteredDegree, triad: TriadQuality) -> RomanNumeral {
+        RomanNumeral {
+            degree,
+            triad,
+            seventh: None,
+            inversion: 0,
+            secondary: None,
+        }
+    }
+
+    /// Adds a seventh, which should be one of `MIN_7`, `MAJ_7` or `DIM_7`.
+    pub fn with_seventh(&self, seventh: SpelledInterval) -> RomanNumeral {
+        RomanNumeral {
+            seventh: Some(seventh),
+            ..self.clone()
+        }
+    }
+
+    /// Puts the `inversion`th chord tone in the bass, e.g. 2 for `V64`.
+    pub fn with_inversion(&self, inversion: u8) -> RomanNumeral {
+        RomanNumeral {
+            inversion,
+            ..self.clone()
+        }
+    }
+
+    /// Applies the numeral to the key of `target`, e.g. `V` of `V` gives `V/V`.
+    pub fn of(&self, target: RomanNumeral) -> RomanNumeral {
+        RomanNumeral {
+            secondary: Some(Box::new(target)),
+            ..self.clone()
+        }
+    }
+
+    pub fn degree(&self) -> AlteredDegree {
+        self.degree
+    }
+
+    pub fn triad(&self) -> TriadQuality {
+        self.triad
+    }
+
+    pub fn seventh(&self) -> Option<SpelledInterval> {
+        self.seventh
+    }
+
+    pub fn inversion(&self) -> u8 {
+        self.inversion
+    }
+
+    /// The numeral this one is applied to, e.g. the second `V` in `V/V`.
+    pub fn secondary(&self) -> Option<&RomanNumeral> {
+        self.secondary.as_deref()
+    }
+
+    /// The chord this numeral stands for in `key`. Secondary numerals are realised in the major key
+    /// of the chord they are applied to.
+    pub fn realise(&self, key: &Key) -> Chord {
+        let tonic = match &self.secondary {
+            Some(target) => target.realise(key).root(),
+            None => key.tonic(),
+        };
+        let mut intervals = self.triad.intervals().to_vec();
+        intervals.extend(self.seventh);
+        Chord::new(degree_root(tonic, self.degree), &intervals).inversion(self.inversion as usize)
+    }
+
+    /// Names `chord` in `key`, or `None` if it is not a triad or seventh chord.
+    ///
+    /// Chromatic major, dominant seventh and diminished chords that lead to a diatonic chord other
+    /// than the tonic are analysed as secondary dominants and leading tone chords, e.g. `V7/ii` or
+    /// `vii°7/V`.
+    pub fn analyse(chord: &Chord, key: &Key) -> Option<RomanNumeral> {
+        let fifth = chord.fifth().unwrap_or(PER_5);
+        let triad = TriadQuality::from_intervals(chord.third()?.simple(), fifth.simple())?;
+        let seventh = chord
+            .seventh()
+            .map(SpelledInterval::simple)
+            .filter(|s| [MIN_7, MAJ_7, DIM_7].contains(s));
+        let bass = chord.bass().pitch_class();
+        let inversion = [Some(PER_1), chord.third(), chord.fifth(), seventh]
+            .iter()
+            .position(|t| t.is_some_and(|t| chord.root().transpose(t).pitch_class() == bass))
+            .unwrap_or(0);
+
+        let plain = RomanNumeral {
+            degree: major_degree(key.tonic(), chord.root()),
+            triad,
+            seventh,
+            inversion: inversion as u8,
+            secondary: None,
+        };
+        if chord.pitch_class_set().is_subset(key.pitch_class_set()) {
+            return Some(plain);
+        }
+
+        for scale_degree in 1..DIATONIC_COUNT {
+            let target = key.diatonic_chord(scale_degree, 3);
+            let target_triad = TriadQuality::from_intervals(target.third()?, target.fifth()?);
+            if target_triad.is_none() || target_triad == Some(TriadQuality::Diminished) {
+                continue;
+            }
+            let degree = major_degree(target.root(), chord.root());
+            let is_dominant = degree == Degree::new(4).into()
+                && triad == TriadQuality::Major
+                && seventh.is_none_or(|s| s == MIN_7);
+            let is_leading_tone = degree == Degree::new(6).into()
+                && triad == TriadQuality::Diminished
+                && seventh != Some(MAJ_7);
+            if is_dominant || is_leading_tone {
+                return Some(RomanNumeral {
+                    degree,
+                    secondary: Some(Box::new(RomanNumeral::analyse(&target, key)?)),
+                    ..plain
+                });
+            }
+        }
+        Some(plain)
+    }
+}
+
+/// Prints e.g. `V7`, `ii65`, `bVII`, `viiø7`, `I64` or `V7/V`.
+impl std::fmt::Display for RomanNumeral {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let numeral = self.degree.degree().to_string();
+        write!(f, "{}", self.degree.accidental())?;
+        match self.triad {
+            TriadQuality::Major | TriadQuality::Augmented => f.write_str(&numeral)?,
+            TriadQuality::Minor | TriadQuality::Diminished => {
+                f.write_str(&numeral.to_lowercase())?
+            }
+        }
+        match (self.triad, self.seventh) {
+            (TriadQuality::Diminished, Some(MIN_7)) => f.write_str("ø")?,
+            (TriadQuality::Diminished, _) => f.write_str("°")?,
+            (TriadQuality::Augmented, _) => f.write_str("+")?,
+            _ => {}
+        }
+        if self.seventh == Some(MAJ_7) {
+            f.write_str("M")?;
+        }
+        let figures = match (self.seventh.is_some(), self.inversion) {
+            (false, 1) => "6",
+            (false, 2) => "64",
+            (true, 0) => "7",
+            (true, 1) => "65",
+            (true, 2) => "43",
+            (true, 3) => "42",
+            _ => "",
+        };
+        f.write_str(figures)?;
+        if let Some(secondary) = &self.secondary {
+            write!(f, "/{}", secondary)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses e.g. `V7`, `bVII`, `ii6/5`, `vii°7/V` or `IM7`.
+///
+/// Upper case numerals are major and lower case ones minor, unless marked `°`, `ø` or `+`.
+/// Figures may be written with or without slashes, e.g. `64` or `6/4`.
+impl std::str::FromStr for RomanNumeral {
+    type Err = ParseRomanNumeralError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(ParseRomanNumeralError::Empty);
+        }
+        let secondary_ix = s
+            .match_indices('/')
+            .map(|(ix, _)| ix)
+            .find(|&ix| !s[ix + 1..].starts_with(|c: char| c.is_ascii_digit()));
+        let (s, secondary) = match secondary_ix {
+            Some(ix) => (&s[..ix], Some(Box::new(s[ix + 1..].parse()?))),
+            None => (s, None),
+        };
+
+        let (accidental, rest) = strip_accidental(s)
+            .ok_or_else(|| ParseRomanNumeralError::UnexpectedToken(s.to_string()))?;
+        let numeral_len = rest
+            .find(|c| !['I', 'V', 'i', 'v'].contains(&c))
+            .unwrap_or(rest.len());
+        let (numeral, rest) = rest.split_at(numeral_len);
+        let is_upper = numeral.chars().all(|c| c.is_ascii_uppercase());
+        if !is_upper && numeral.chars().any(|c| c.is_ascii_uppercase()) {
+            return Err(ParseRomanNumeralError::MixedCase(numeral.to_string()));
+        }
+        let degree = numeral
+            .parse::<Degree>()
+            .map_err(ParseRomanNumeralError::InvalidDegree)?;
+        if degree.index() >= DIATONIC_COUNT as i8 {
+            return Err(ParseRomanNumeralError::BeyondSeventh(numeral.to_string()));
+        }
+
+        let (marker, rest) = strip_any(rest, &["°", "o", "ø", "Ø", "+", "⁺"]).unwrap_or(("", rest));
+        let triad = match (marker, is_upper) {
+            ("°", _) | ("o", _) | ("ø", _) | ("Ø", _) => TriadQuality::Diminished,
+            ("+", _) | ("⁺", _) => TriadQuality::Augmented,
+            (_, true) => TriadQuality::Major,
+            (_, false) => TriadQuality::Minor,
+        };
+        let (major_seventh, rest) = match strip_any(rest, &["maj", "M", "Δ"]) {
+            Some((_, rest)) => (true, rest),
+            None => (false, rest),
+        };
+        let figures = [
+            "7", "6/5", "65", "4/3", "43", "4/2", "42", "2", "6/4", "64", "6",
+        ];
+        let (figure, rest) = strip_any(rest, &figures).unwrap_or(("", rest));
+        if !rest.is_empty() {
+            return Err(ParseRomanNumeralError::UnexpectedToken(rest.to_string()));
+        }
+
+        let is_half_diminished = marker == "ø" || marker == "Ø";
+        let (has_seventh, inversion) = match figure {
+            "7" => (true, 0),
+            "6/5" | "65" => (true, 1),
+            "4/3" | "43" => (true, 2),
+            "4/2" | "42" | "2" => (true, 3),
+            "6" => (false, 1),
+            "6/4" | "64" => (false, 2),
+            _ => (is_half_diminished, 0),
+        };
+        if major_seventh && !has_seventh {
+            return Err(ParseRomanNumeralError::UnexpectedToken("M".to_string()));
+        }
+        let seventh = match (has_seventh, major_seventh, marker) {
+            (false, _, _) => None,
+            (true, true, _) => Some(MAJ_7),
+            (true, false, "°") | (true, false, "o") => Some(DIM_7),
+            (true, false, _) => Some(MIN_7),
+        };
+
+        Ok(RomanNumeral {
+            degree: AlteredDegree::new(Accidental::new(accidental), degree),
+            triad,
+            seventh,
+            inversion,
+            secondary,
+        })
+    }
+}
+
+/// The root of `degree` counted along the major scale on `tonic`.
+fn degree_root(tonic: SpelledPitchClass, degree: AlteredDegree) -> SpelledPitchClass {
+    let steps = degree.degree().index().rem_euclid(DIATONIC_COUNT as i8) as usize;
+    let natural = tonic + MAJOR_FAMILY_SPELLED[steps];
+    let accidental = natural.accidental().semitones() + degree.accidental().semitones();
+    SpelledPitchClass::new(natural.letter(), Accidental::new(accidental))
+}
+
+/// The degree of `pc` relative to the major scale on `tonic`, e.g. `bVI` for `Ab` in C.
+fn major_degree(tonic: SpelledPitchClass, pc: SpelledPitchClass) -> AlteredDegree {
+    let steps = (pc.letter().index() - tonic.letter().index()).rem_euclid(DIATONIC_COUNT as i8);
+    let natural = tonic + MAJOR_FAMILY_SPELLED[steps as usize];
+    let semitones = (pc.pitch_class() - natural.pitch_class()).semitones();
+    let semitones = if semitones > CHROMATIC_COUNT as i8 / 2 {
+        semitones - CHROMATIC_COUNT as i8
+    } else {
+        semitones
+    };
+    AlteredDegree::new(Accidental::new(semitones), Degree::new(steps))
+}
+
+/******************************************************************************
+* TESTS
+******************************************************************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NUMERALS: [&str; 23] = [
+        "I", "ii", "iii", "IV", "V", "vi", "vii°", "V7", "ii7", "IM7", "viiø7", "vii°7", "I6",
+        "I64", "V65", "V43", "V42", "bVII", "bVI", "III+", "V7/V", "vii°7/V", "V/ii",
+    ];
+
+    fn c_major() -> Key {
+        Key::major("C".parse().unwrap())
+    }
+
+    #[test]
+    fn numerals_round_trip() {
+        for &s in NUMERALS.iter() {
+            let numeral: RomanNumeral = s.parse().unwrap();
+            assert_eq!(numeral.to_string(), s);
+            assert_eq!(numeral.to_string().parse(), Ok(numeral));
+        }
+    }
+
+    #[test]
+    fn realised_numerals_analyse_back() {
+        let key = c_major();
+        for &s in NUMERALS.iter() {
+            let numeral: RomanNumeral = s.parse().unwrap();
+            assert_eq!(
+                RomanNumeral::analyse(&numeral.realise(&key), &key),
+                Some(numeral),
+                "{}",
+                s
+            );
+        }
+    }
+
+    #[test]
+    fn chromatic_tonic_chords_are_secondary() {
+        let key = c_major();
+        let analyse = |s: &str| RomanNumeral::analyse(&s.parse().unwrap(), &key).unwrap();
+        assert_eq!(analyse("C7").to_string(), "V7/IV");
+        assert_eq!(analyse("Cmaj7").to_string(), "IM7");
+        assert_eq!(analyse("Cm").to_string(), "i");
+    }
+
+    #[test]
+    fn invalid_numerals() {
+        for &s in ["", "Vi", "VIII", "b#VII", "V/"].iter() {
+            assert!(s.parse::<RomanNumeral>().is_err(), "{}", s);
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use rand::{seq::SliceRandom, Rng};
+
+use super::super::{
+    BeatDuration, BeatTime, ChromaticRange, Interval, Line, Meter, Note, PitchClass, EN, FIFTH,
+    FIRST, QN, THIRD,
+};
+use super::Improviser;
+
+/// Semitones over which the chance of moving to a note falls by a factor of e.
+const STEP_SPREAD: f64 = 1.5;
+/// How much more likely chord tones are than other notes on the downbeat, less so on weaker
+/// positions.
+const CHORD_TONE_WEIGHT: f64 = 4.;
+/// How much less likely a note is to repeat the one before.
+const REPEAT_WEIGHT: f64 = 0.3;
+
+impl Improviser {
+    pub fn new(range: ChromaticRange) -> Improviser {
+        Improviser {
+            range,
+            note_values: vec![EN, EN, QN, QN + EN],
+            rest_chance: 0.1,
+            meter: Meter::simple(4, QN),
+        }
+    }
+
+    /// Durations to pick from, repeated ones being picked more often.
+    pub fn with_note_values(&self, note_values: &[BeatDuration]) -> Improviser {
+        assert!(!note_values.is_empty());
+        Improviser {
+            note_values: note_values.to_vec(),
+            ..self.clone()
+        }
+    }
+
+    /// The chance, from 0 to 1, of resting instead of playing each note.
+    pub fn with_rest_chance(&self, rest_chance: f64) -> Improviser {
+        Improviser {
+            rest_chance: rest_chance.clamp(0., 1.),
+            ..self.clone()
+        }
+    }
+
+    /// The meter lines are improvised in, starting on a downbeat.
+    pub fn with_meter(&self, meter: &Meter) -> Improviser {
+        Improviser {
+            meter: meter.clone(),
+            ..self.clone()
+        }
+    }
+
+    pub fn range(&self) -> ChromaticRange {
+        self.range
+    }
+
+    pub fn note_values(&self) -> &[BeatDuration] {
+        &self.note_values
+    }
+
+    pub fn rest_chance(&self) -> f64 {
+        self.rest_chance
+    }
+
+    pub fn meter(&self) -> &Meter {
+        &self.meter
+    }
+
+    /// A line lasting `length` from time zero over the chord on `root`, using the notes of
+    /// `chord_scale`, e.g. from `ScaleFamily::chord_scale_0`, and carrying on from `previous`.
+    pub fn improvise<R: Rng>(
+        &self,
+        root: PitchClass,
+        chord_scale: &[Interval],
+        length: BeatDuration,
+        previous: Option<Note>,
+        rng: &mut R,
+    ) -> Line {
+        let lower = usize::from(self.range.lower_sounding());
+        let upper = usize::from(self.range.upper_sounding());
+        let candidates: Vec<Note> = (lower..=upper)
+            .map(|n| Note::new(n as i8))
+            .filter(|note| chord_scale.contains(&(note.pitch_class() - root)))
+            .collect();
+        let chord_tones: Vec<PitchClass> = [FIRST, THIRD, FIFTH]
+            .iter()
+            .filter_map(|&i| chord_scale.get(i))
+            .map(|&interval| root + interval)
+            .collect();
+
+        let mut line = Line::new();
+        if candidates.is_empty() {
+            return line;
+        }
+        let mut previous = previous.unwrap_or_else(|| Note::new(((lower + upper) / 2) as i8));
+        let end = BeatTime::zero() + length;
+        let mut onset = BeatTime::zero();
+        while onset < end {
+            let remaining = end - onset;
+            let duration = self
+                .note_values
+                .iter()
+                .filter(|&&value| value <= remaining)
+                .copied()
+                .collect::<Vec<BeatDuration>>()
+                .choose(rng)
+                .copied()
+                .unwrap_or(remaining);
+            if !rng.gen_bool(self.rest_chance) {
+                let emphasis = self.meter.emphasis(onset);
+                let note = *candidates
+                    .choose_weighted(rng, |&note| {
+                        let distance =
+                            (usize::from(note) as f64 - usize::from(previous) as f64).abs();
+                        let mut weight = (-distance / STEP_SPREAD).exp();
+                        if chord_tones.contains(&note.pitch_class()) {
+                            weight *= 1. + (CHORD_TONE_WEIGHT - 1.) * emphasis;
+                        }
+                        if note == previous {
+                            weight *= REPEAT_WEIGHT;
+                        }
+                        weight
+                    })
+                    .unwrap();
+                line.push(note, onset, duration);
+                previous = note;
+            }
+            onset += duration;
+        }
+        line
+    }
+}
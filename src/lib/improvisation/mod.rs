@@ -0,0 +1,15 @@
+mod improviser;
+
+use super::{BeatDuration, ChromaticRange, Meter};
+
+/// https://en.wikipedia.org/wiki/Musical_improvisation
+///
+/// Plays freely over a chord within its chord-scale, stepping more than leaping and landing on
+/// chord tones on strong beats of its meter.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Improviser {
+    range: ChromaticRange,
+    note_values: Vec<BeatDuration>,
+    rest_chance: f64,
+    meter: Meter,
+}
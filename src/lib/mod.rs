@@ -1,7 +1,21 @@
 #![allow(non_upper_case_globals)]
 
+mod counterpoint;
+mod dissonance;
+mod form;
+mod harmony;
+mod improvisation;
+mod motif;
 mod rhythm;
 mod pitch;
+mod voice_leading;
 
+pub use counterpoint::*;
+pub use dissonance::*;
+pub use form::*;
+pub use harmony::*;
+pub use improvisation::*;
+pub use motif::*;
 pub use rhythm::*;
 pub use pitch::*;
+pub use voice_leading::*;
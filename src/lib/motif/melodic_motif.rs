@@ -0,0 +1,230 @@
+use rand::{seq::SliceRandom, Rng};
+
+use super::super::{BeatDuration, Interval, Line, Note, PitchClass, Rhythm, CHROMATIC_COUNT, EN};
+use super::{Motif, MotifTransformation};
+
+/// The most scale steps a transposition moves a motif by.
+const MAX_TRANSPOSITION: i8 = 3;
+
+impl Motif {
+    /// The first step is where the motif starts relative to where it is played from, usually 0.
+    pub fn new(steps: &[i8], durations: &[BeatDuration]) -> Motif {
+        assert!(!steps.is_empty());
+        assert_eq!(steps.len(), durations.len());
+        Motif {
+            steps: steps.to_vec(),
+            rhythm: Rhythm::from_durations(durations),
+        }
+    }
+
+    /// The shape of `line` in the scale of `scale` on `tonic`, starting from step 0 and lasting
+    /// until the next note starts or the line ends. Notes outside the scale count as the scale note
+    /// below them.
+    pub fn from_line(line: &Line, tonic: PitchClass, scale: &[Interval]) -> Option<Motif> {
+        if line.is_empty() || scale.is_empty() {
+            return None;
+        }
+        let first = scale_position(line.notes()[0], tonic, scale);
+        let steps: Vec<i8> = line
+            .notes()
+            .iter()
+            .map(|&note| (scale_position(note, tonic, scale) - first) as i8)
+            .collect();
+        let durations: Vec<BeatDuration> = (0..line.len())
+            .map(|i| match line.onsets().get(i + 1) {
+                Some(&next) => next - line.onsets()[i],
+                None => line.durations()[i],
+            })
+            .collect();
+        Some(Motif::new(&steps, &durations))
+    }
+
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    /// Always false, as motifs have at least one note.
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    pub fn steps(&self) -> &[i8] {
+        &self.steps
+    }
+
+    pub fn durations(&self) -> &[BeatDuration] {
+        self.rhythm.durations()
+    }
+
+    /// The rhythm of the motif, a note on each duration.
+    pub fn rhythm(&self) -> &Rhythm {
+        &self.rhythm
+    }
+
+    /// How long the motif lasts.
+    pub fn length(&self) -> BeatDuration {
+        self.rhythm.length()
+    }
+
+    /// The motif played in the scale of `scale` on `tonic` from `origin`, taken as the scale note
+    /// at or below it, starting at time zero. None when a note falls outside the MIDI range.
+    pub fn line(&self, origin: Note, tonic: PitchClass, scale: &[Interval]) -> Option<Line> {
+        let origin = scale_position(origin, tonic, scale);
+        let mut line = Line::new();
+        for (&step, (onset, duration)) in self.steps.iter().zip(self.rhythm.notes()) {
+            line.push(
+                note_at(origin + step as i32, tonic, scale)?,
+                onset,
+                duration,
+            );
+        }
+        Some(line)
+    }
+
+    /// Moved `steps` up the scale, or down when negative. None when a step no longer fits an i8.
+    pub fn transposed(&self, steps: i8) -> Option<Motif> {
+        Some(Motif {
+            steps: self
+                .steps
+                .iter()
+                .map(|&s| s.checked_add(steps))
+                .collect::<Option<_>>()?,
+            ..self.clone()
+        })
+    }
+
+    /// Mirrored around the first note, so rising steps fall and vice versa. None when a step no
+    /// longer fits an i8.
+    pub fn inverted(&self) -> Option<Motif> {
+        let first = self.steps[0];
+        Some(Motif {
+            steps: self
+                .steps
+                .iter()
+                .map(|&s| first.checked_sub(s)?.checked_add(first))
+                .collect::<Option<_>>()?,
+            ..self.clone()
+        })
+    }
+
+    pub fn retrograde(&self) -> Motif {
+        Motif {
+            steps: self.steps.iter().rev().copied().collect(),
+            rhythm: self.rhythm.reversed(),
+        }
+    }
+
+    /// The motif with its rhythm scaled, see `Rhythm::scaled`.
+    pub fn scaled(&self, numerator: u64, denominator: u64) -> Option<Motif> {
+        Some(Motif {
+            rhythm: self.rhythm.scaled(numerator, denominator)?,
+            ..self.clone()
+        })
+    }
+
+    /// The `len` notes starting at the note at `start`.
+    pub fn fragment(&self, start: usize, len: usize) -> Motif {
+        Motif {
+            steps: self.steps[start..start + len].to_vec(),
+            rhythm: self.rhythm.fragment(start, len),
+        }
+    }
+
+    /// Whether `transformation` leaves a motif worth developing, i.e. diminution stops at
+    /// sixteenth notes and halves every duration evenly, fragments keep at least two notes, and
+    /// transposition by up to `MAX_TRANSPOSITION` steps or inversion keeps the steps in range.
+    pub fn can_transform(&self, transformation: MotifTransformation) -> bool {
+        match transformation {
+            MotifTransformation::Transposition => {
+                self.transposed(-MAX_TRANSPOSITION).is_some()
+                    && self.transposed(MAX_TRANSPOSITION).is_some()
+            }
+            MotifTransformation::Inversion => self.inverted().is_some(),
+            MotifTransformation::Diminution => {
+                self.durations().iter().all(|&d| d >= EN) && self.scaled(1, 2).is_some()
+            }
+            MotifTransformation::Fragmentation => self.len() > 2,
+            _ => true,
+        }
+    }
+
+    /// The motif transformed by `transformation`, with a random amount where it takes one. Panics
+    /// where `can_transform` does not hold.
+    pub fn transform<R: Rng>(&self, transformation: MotifTransformation, rng: &mut R) -> Motif {
+        match transformation {
+            MotifTransformation::Transposition => {
+                let steps = rng.gen_range(1, MAX_TRANSPOSITION + 1);
+                let steps = if rng.gen() { steps } else { -steps };
+                self.transposed(steps).expect("transposed out of range")
+            }
+            MotifTransformation::Inversion => self.inverted().expect("inverted out of range"),
+            MotifTransformation::Retrograde => self.retrograde(),
+            MotifTransformation::Augmentation => self.scaled(2, 1).unwrap(),
+            MotifTransformation::Diminution => self.scaled(1, 2).expect("uneven diminution"),
+            MotifTransformation::Fragmentation => {
+                let len = rng.gen_range(2, self.len());
+                let start = rng.gen_range(0, self.len() - len + 1);
+                self.fragment(start, len)
+            }
+        }
+    }
+
+    /// The motif transformed in one of the ways that apply to it, picked at random.
+    pub fn develop<R: Rng>(&self, rng: &mut R) -> Motif {
+        let transformations: Vec<MotifTransformation> = MotifTransformation::ALL
+            .iter()
+            .copied()
+            .filter(|&t| self.can_transform(t))
+            .collect();
+        self.transform(*transformations.choose(rng).unwrap(), rng)
+    }
+}
+
+/// Counts scale notes from the tonic of MIDI octave 0, taking notes outside the scale as the scale
+/// note below them.
+fn scale_position(note: Note, tonic: PitchClass, scale: &[Interval]) -> i32 {
+    let offset = usize::from(note) as i32 - usize::from(tonic) as i32;
+    let octave = offset.div_euclid(CHROMATIC_COUNT as i32);
+    let within = offset.rem_euclid(CHROMATIC_COUNT as i32);
+    let degree = scale
+        .iter()
+        .rposition(|&interval| (interval.semitones() as i32) <= within)
+        .unwrap_or(0);
+    octave * scale.len() as i32 + degree as i32
+}
+
+fn note_at(position: i32, tonic: PitchClass, scale: &[Interval]) -> Option<Note> {
+    let octave = position.div_euclid(scale.len() as i32);
+    let degree = position.rem_euclid(scale.len() as i32) as usize;
+    let note = usize::from(tonic) as i32
+        + octave * CHROMATIC_COUNT as i32
+        + scale[degree].semitones() as i32;
+    if note < 0 || note > i8::MAX as i32 {
+        return None;
+    }
+    Note::try_new(note as i8)
+}
+
+/******************************************************************************
+* TESTS
+******************************************************************************/
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::QN;
+    use super::*;
+
+    #[test]
+    fn transformations_keeping_steps_in_range() {
+        let motif = Motif::new(&[0, 120], &[QN, QN]);
+        assert_eq!(motif.transposed(7).unwrap().steps(), &[7, 127]);
+        assert_eq!(motif.transposed(10), None);
+        assert_eq!(motif.transposed(-10).unwrap().steps(), &[-10, 110]);
+        assert!(motif.can_transform(MotifTransformation::Transposition));
+        assert!(!Motif::new(&[0, 125], &[QN, QN]).can_transform(MotifTransformation::Transposition));
+        assert_eq!(motif.inverted().unwrap().steps(), &[0, -120]);
+        let motif = Motif::new(&[-100, 100], &[QN, QN]);
+        assert_eq!(motif.inverted(), None);
+        assert!(!motif.can_transform(MotifTransformation::Inversion));
+    }
+}
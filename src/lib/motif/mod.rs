@@ -0,0 +1,39 @@
+mod melodic_motif;
+mod motif_memory;
+mod transformation;
+
+use super::Rhythm;
+
+/// https://en.wikipedia.org/wiki/Motif_(music)
+///
+/// A melodic shape as steps through a scale, relative to wherever it is played from, together with
+/// its rhythm. Rests are left out, so each note lasts until the next one starts.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Motif {
+    steps: Vec<i8>,
+    rhythm: Rhythm,
+}
+
+/// https://en.wikipedia.org/wiki/Variation_(music)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MotifTransformation {
+    /// Moves the motif up or down the scale.
+    Transposition,
+    /// Mirrors the steps around the first note.
+    Inversion,
+    /// Plays the motif backwards.
+    Retrograde,
+    /// Doubles the durations.
+    Augmentation,
+    /// Halves the durations.
+    Diminution,
+    /// Keeps only part of the motif.
+    Fragmentation,
+}
+
+/// Motifs heard recently, to be recalled and developed instead of making up new ones.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MotifMemory {
+    motifs: Vec<Motif>,
+    capacity: usize,
+}
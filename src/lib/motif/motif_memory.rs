@@ -0,0 +1,45 @@
+use rand::{seq::SliceRandom, Rng};
+
+use super::{Motif, MotifMemory};
+
+impl MotifMemory {
+    /// Remembers up to `capacity` motifs, forgetting the oldest first.
+    pub fn new(capacity: usize) -> MotifMemory {
+        assert!(capacity > 0);
+        MotifMemory {
+            motifs: Vec::new(),
+            capacity,
+        }
+    }
+
+    pub fn motifs(&self) -> &[Motif] {
+        &self.motifs
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn remember(&mut self, motif: Motif) {
+        if self.motifs.contains(&motif) {
+            return;
+        }
+        if self.motifs.len() >= self.capacity {
+            self.motifs.remove(0);
+        }
+        self.motifs.push(motif);
+    }
+
+    /// A remembered motif as it was.
+    pub fn recall<R: Rng>(&self, rng: &mut R) -> Option<Motif> {
+        self.motifs.choose(rng).cloned()
+    }
+
+    /// A remembered motif developed by a random transformation, itself remembered too so that
+    /// later developments may build on it.
+    pub fn develop<R: Rng>(&mut self, rng: &mut R) -> Option<Motif> {
+        let motif = self.recall(rng)?.develop(rng);
+        self.remember(motif.clone());
+        Some(motif)
+    }
+}
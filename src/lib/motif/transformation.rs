@@ -0,0 +1,25 @@
+use super::MotifTransformation;
+
+impl MotifTransformation {
+    pub const ALL: [MotifTransformation; 6] = [
+        MotifTransformation::Transposition,
+        MotifTransformation::Inversion,
+        MotifTransformation::Retrograde,
+        MotifTransformation::Augmentation,
+        MotifTransformation::Diminution,
+        MotifTransformation::Fragmentation,
+    ];
+}
+
+impl std::fmt::Display for MotifTransformation {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(match self {
+            MotifTransformation::Transposition => "transposition",
+            MotifTransformation::Inversion => "inversion",
+            MotifTransformation::Retrograde => "retrograde",
+            MotifTransformation::Augmentation => "augmentation",
+            MotifTransformation::Diminution => "diminution",
+            MotifTransformation::Fragmentation => "fragmentation",
+        })
+    }
+}
@@ -0,0 +1,36 @@
+use super::Accidental;
+
+impl Accidental {
+    pub fn new(x: i8) -> Accidental {
+        Accidental(x)
+    }
+
+    /// Semitones the accidental raises (or lowers when negative) the natural note by.
+    pub fn semitones(self) -> i8 {
+        self.0
+    }
+
+    pub fn is_natural(self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl std::fmt::Display for Accidental {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let symbol = if self.0 < 0 { "b" } else { "#" };
+        for _ in 0..self.0.abs() {
+            f.write_str(symbol)?;
+        }
+        Ok(())
+    }
+}
+
+pub mod accidental_constants {
+    use super::Accidental;
+
+    pub const DOUBLE_FLAT: Accidental = Accidental(-2);
+    pub const FLAT: Accidental = Accidental(-1);
+    pub const NATURAL: Accidental = Accidental(0);
+    pub const SHARP: Accidental = Accidental(1);
+    pub const DOUBLE_SHARP: Accidental = Accidental(2);
+}
@@ -0,0 +1,178 @@
+use super::interval_constants::PER_1;
+use super::{ChromaticRange, Interval, Note, CHROMATIC_COUNT};
+
+impl ChromaticRange {
+    /// A concert pitch range, i.e. written as it sounds.
+    pub fn new(lower_sounding: Note, upper_sounding: Note) -> ChromaticRange {
+        ChromaticRange::transposing(lower_sounding, upper_sounding, PER_1)
+    }
+
+    /// `write_transposition` is the interval from sounding to written pitch, e.g. `MAJ_2` for a Bb clarinet.
+    pub fn transposing(
+        lower_sounding: Note,
+        upper_sounding: Note,
+        write_transposition: Interval,
+    ) -> ChromaticRange {
+        assert!(lower_sounding <= upper_sounding);
+        ChromaticRange {
+            lower_sounding,
+            upper_sounding,
+            write_transposition,
+        }
+    }
+
+    pub fn lower_sounding(&self) -> Note {
+        self.lower_sounding
+    }
+
+    pub fn upper_sounding(&self) -> Note {
+        self.upper_sounding
+    }
+
+    pub fn lower_written(&self) -> Note {
+        self.written(self.lower_sounding)
+    }
+
+    pub fn upper_written(&self) -> Note {
+        self.written(self.upper_sounding)
+    }
+
+    pub fn write_transposition(&self) -> Interval {
+        self.write_transposition
+    }
+
+    /// Converts a sounding note into how it is notated for this range.
+    pub fn written(&self, sounding: Note) -> Note {
+        sounding + self.write_transposition
+    }
+
+    /// Converts a notated note into how it sounds for this range.
+    pub fn sounding(&self, written: Note) -> Note {
+        written - self.write_transposition
+    }
+
+    /// Whether the sounding `note` is playable.
+    pub fn contains(&self, note: Note) -> bool {
+        self.lower_sounding <= note && note <= self.upper_sounding
+    }
+
+    /// Width of the range in semitones.
+    pub fn span(&self) -> Interval {
+        Interval(self.upper_sounding.0 - self.lower_sounding.0)
+    }
+
+    /// Moves the sounding `note` to the nearest bound if it lies outside the range.
+    pub fn clamp(&self, note: Note) -> Note {
+        if note < self.lower_sounding {
+            self.lower_sounding
+        } else if note > self.upper_sounding {
+            self.upper_sounding
+        } else {
+            note
+        }
+    }
+
+    /// Moves the sounding `note` by whole octaves into the range, as little as possible.
+    ///
+    /// Returns `None` when the range is narrower than an octave and misses the pitch class of `note`.
+    pub fn fold_into(&self, note: Note) -> Option<Note> {
+        const OCTAVE: i16 = CHROMATIC_COUNT as i16;
+        let lower = self.lower_sounding.0 as i16;
+        let upper = self.upper_sounding.0 as i16;
+        let x = note.0 as i16;
+
+        let folded = if x < lower {
+            lower + (x - lower).rem_euclid(OCTAVE)
+        } else if x > upper {
+            upper - (upper - x).rem_euclid(OCTAVE)
+        } else {
+            x
+        };
+
+        if lower <= folded && folded <= upper {
+            Some(Note(folded as i8))
+        } else {
+            None
+        }
+    }
+}
+
+pub mod chromatic_range_constants {
+    use super::super::interval_constants::{MAJ_2, PER_1, PER_8};
+    use super::super::note_constants::*;
+    use super::ChromaticRange;
+
+    pub const VIOLIN: ChromaticRange = ChromaticRange {
+        lower_sounding: G3,
+        upper_sounding: A7,
+        write_transposition: PER_1,
+    };
+    pub const VIOLA: ChromaticRange = ChromaticRange {
+        lower_sounding: C3,
+        upper_sounding: E6,
+        write_transposition: PER_1,
+    };
+    pub const CELLO: ChromaticRange = ChromaticRange {
+        lower_sounding: C2,
+        upper_sounding: A5,
+        write_transposition: PER_1,
+    };
+    /// Written an octave above sounding.
+    pub const DOUBLE_BASS: ChromaticRange = ChromaticRange {
+        lower_sounding: E1,
+        upper_sounding: G4,
+        write_transposition: PER_8,
+    };
+    pub const FLUTE: ChromaticRange = ChromaticRange {
+        lower_sounding: C4,
+        upper_sounding: D7,
+        write_transposition: PER_1,
+    };
+    /// Written a major second above sounding.
+    pub const CLARINET_BB: ChromaticRange = ChromaticRange {
+        lower_sounding: D3,
+        upper_sounding: Bb6,
+        write_transposition: MAJ_2,
+    };
+    pub const SOPRANO: ChromaticRange = ChromaticRange {
+        lower_sounding: C4,
+        upper_sounding: A5,
+        write_transposition: PER_1,
+    };
+    pub const ALTO: ChromaticRange = ChromaticRange {
+        lower_sounding: F3,
+        upper_sounding: D5,
+        write_transposition: PER_1,
+    };
+    pub const TENOR: ChromaticRange = ChromaticRange {
+        lower_sounding: C3,
+        upper_sounding: A4,
+        write_transposition: PER_1,
+    };
+    pub const BASS: ChromaticRange = ChromaticRange {
+        lower_sounding: E2,
+        upper_sounding: E4,
+        write_transposition: PER_1,
+    };
+
+    pub const INSTRUMENT_RANGES: [(&str, ChromaticRange); 10] = [
+        ("violin", VIOLIN),
+        ("viola", VIOLA),
+        ("cello", CELLO),
+        ("double bass", DOUBLE_BASS),
+        ("flute", FLUTE),
+        ("clarinet in Bb", CLARINET_BB),
+        ("soprano", SOPRANO),
+        ("alto", ALTO),
+        ("tenor", TENOR),
+        ("bass", BASS),
+    ];
+
+    /// Looks up a preset in `INSTRUMENT_RANGES` by name, ignoring case.
+    pub fn by_name(name: &str) -> Option<ChromaticRange> {
+        INSTRUMENT_RANGES
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|&(_, range)| range)
+    }
+}
@@ -1,7 +1,169 @@
-use super::Degree;
+use super::{Accidental, AlteredDegree, Degree};
+
+/******************************************************************************
+* ERRORS
+******************************************************************************/
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParseDegreeError {
+    Empty,
+    InvalidNumeral(String),
+    UnexpectedAccidental,
+}
+
+impl std::fmt::Display for ParseDegreeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ParseDegreeError::Empty => write!(f, "expected a roman numeral, found nothing"),
+            ParseDegreeError::InvalidNumeral(s) => write!(f, "invalid roman numeral \"{}\"", s),
+            ParseDegreeError::UnexpectedAccidental => {
+                write!(f, "a plain degree cannot be altered, use an altered degree")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseDegreeError {}
+
+/******************************************************************************
+* IMPLS
+******************************************************************************/
 
 impl Degree {
-    pub fn new(x: i8) -> Degree {
+    pub const fn new(x: i8) -> Degree {
         Degree(x)
     }
+
+    /// The zero based degree, e.g. 4 for `V`.
+    pub fn index(self) -> i8 {
+        self.0
+    }
+}
+
+impl AlteredDegree {
+    pub fn new(accidental: Accidental, degree: Degree) -> AlteredDegree {
+        AlteredDegree { accidental, degree }
+    }
+
+    pub fn accidental(self) -> Accidental {
+        self.accidental
+    }
+
+    pub fn degree(self) -> Degree {
+        self.degree
+    }
+}
+
+impl From<Degree> for AlteredDegree {
+    fn from(degree: Degree) -> AlteredDegree {
+        AlteredDegree::new(Accidental::default(), degree)
+    }
+}
+
+/// Prints the zero based degree as an upper case roman numeral, e.g. `Degree(4)` as `V`.
+///
+/// Negative degrees have no numeral and are printed as plain numbers.
+impl std::fmt::Display for Degree {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if self.0 < 0 {
+            write!(f, "{}", self.0)
+        } else {
+            f.write_str(&roman_num(self.0 as usize + 1))
+        }
+    }
+}
+
+/// Parses a roman numeral in either case, e.g. `V` or `ii`.
+impl std::str::FromStr for Degree {
+    type Err = ParseDegreeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(ParseDegreeError::Empty);
+        }
+        if s.starts_with(['#', 'b', '♯', '♭']) {
+            return Err(ParseDegreeError::UnexpectedAccidental);
+        }
+        match parse_roman_num(s) {
+            Some(n) if n >= 1 && n <= i8::MAX as usize => Ok(Degree(n as i8 - 1)),
+            _ => Err(ParseDegreeError::InvalidNumeral(s.to_string())),
+        }
+    }
+}
+
+impl std::fmt::Display for AlteredDegree {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}{}", self.accidental, self.degree)
+    }
+}
+
+/// Parses a roman numeral preceded by any number of either sharps or flats, e.g. `bVII`.
+impl std::str::FromStr for AlteredDegree {
+    type Err = ParseDegreeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let numeral_start = s
+            .find(|c| !['#', 'b', '♯', '♭'].contains(&c))
+            .unwrap_or(s.len());
+        let (accidentals, numeral) = s.split_at(numeral_start);
+
+        let sharps = accidentals
+            .chars()
+            .filter(|&c| c == '#' || c == '♯')
+            .count();
+        let flats = accidentals.chars().count() - sharps;
+        if sharps > 0 && flats > 0 {
+            return Err(ParseDegreeError::InvalidNumeral(s.to_string()));
+        }
+
+        Ok(AlteredDegree::new(
+            Accidental::new(sharps as i8 - flats as i8),
+            numeral.parse()?,
+        ))
+    }
+}
+
+fn roman_num(mut n: usize) -> String {
+    let mut result = String::new();
+    for &(name, value) in ROMAN_NUMERALS.iter() {
+        while n >= value {
+            n -= value;
+            result.push_str(name);
+        }
+    }
+    result
 }
+
+/// Accepts only the canonical spelling, so `IIII` is rejected in favour of `IV`.
+fn parse_roman_num(s: &str) -> Option<usize> {
+    let upper = s.to_ascii_uppercase();
+    let mut rest = upper.as_str();
+    let mut n = 0;
+    for &(name, value) in ROMAN_NUMERALS.iter() {
+        while rest.starts_with(name) {
+            rest = &rest[name.len()..];
+            n += value;
+        }
+    }
+    if rest.is_empty() && roman_num(n) == upper {
+        Some(n)
+    } else {
+        None
+    }
+}
+
+const ROMAN_NUMERALS: [(&str, usize); 13] = [
+    ("M", 1000),
+    ("CM", 900),
+    ("D", 500),
+    ("CD", 400),
+    ("C", 100),
+    ("XC", 90),
+    ("L", 50),
+    ("XL", 40),
+    ("X", 10),
+    ("IX", 9),
+    ("V", 5),
+    ("IV", 4),
+    ("I", 1),
+];
@@ -1,41 +1,121 @@
-use super::Interval;
+use super::{Interval, ParseIntervalError, SpelledInterval};
 
 impl Interval {
     pub fn new(interval: i8) -> Interval {
         Interval(interval)
     }
 
+    pub fn semitones(self) -> i8 {
+        self.0
+    }
+
+    pub fn is_descending(self) -> bool {
+        self.0 < 0
+    }
+
+    pub fn abs(self) -> Interval {
+        Interval(self.0.abs())
+    }
+
     pub fn positive_less_than(self, div: i8) -> Interval {
         Interval(self.0.rem_euclid(div))
     }
 
+    pub fn checked_add(self, other: Interval) -> Option<Interval> {
+        self.0.checked_add(other.0).map(Interval)
+    }
+
+    pub fn checked_sub(self, other: Interval) -> Option<Interval> {
+        self.0.checked_sub(other.0).map(Interval)
+    }
+
+    pub fn checked_mul(self, other: i8) -> Option<Interval> {
+        self.0.checked_mul(other).map(Interval)
+    }
+
+    pub fn checked_neg(self) -> Option<Interval> {
+        self.0.checked_neg().map(Interval)
+    }
+
+    /// Stops at the widest intervals an Interval holds.
+    pub fn saturating_add(self, other: Interval) -> Interval {
+        Interval(self.0.saturating_add(other.0))
+    }
+
+    /// Stops at the widest intervals an Interval holds.
+    pub fn saturating_sub(self, other: Interval) -> Interval {
+        Interval(self.0.saturating_sub(other.0))
+    }
+
+    /// Stops at the widest intervals an Interval holds.
+    pub fn saturating_mul(self, other: i8) -> Interval {
+        Interval(self.0.saturating_mul(other))
+    }
+
+    /// Stops at the widest intervals an Interval holds.
+    pub fn saturating_neg(self) -> Interval {
+        Interval(self.0.saturating_neg())
+    }
+
     // TODO: move constness into Neg impl https://github.com/rust-lang/rfcs/pull/2632
     pub const fn neg_const(self) -> Interval {
         Interval(-self.0)
     }
 }
 
+/// Prints the most common spelling, e.g. `m3`, `P5` or `-M2`.
+impl std::fmt::Display for Interval {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        SpelledInterval::from_interval(*self).fmt(f)
+    }
+}
+
+/// Parses any spelling, so both `A4` and `d5` give six semitones.
+impl std::str::FromStr for Interval {
+    type Err = ParseIntervalError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse::<SpelledInterval>().map(Interval::from)
+    }
+}
+
+/// Panics when wider than an Interval holds, like integer overflow does.
 impl std::ops::Add<Interval> for Interval {
     type Output = Self;
 
     fn add(self, other: Interval) -> Self {
-        Interval(self.0 + other.0)
+        self.checked_add(other)
+            .expect("interval addition left the Interval range")
     }
 }
 
+/// Panics when wider than an Interval holds, like integer overflow does.
 impl std::ops::Sub<Interval> for Interval {
     type Output = Self;
 
     fn sub(self, other: Interval) -> Self {
-        Interval(self.0 - other.0)
+        self.checked_sub(other)
+            .expect("interval subtraction left the Interval range")
     }
 }
 
+/// Panics when wider than an Interval holds, like integer overflow does.
+impl std::ops::Mul<i8> for Interval {
+    type Output = Self;
+
+    fn mul(self, other: i8) -> Self {
+        self.checked_mul(other)
+            .expect("interval multiplication left the Interval range")
+    }
+}
+
+/// Panics when wider than an Interval holds, like integer overflow does.
 impl std::ops::Neg for Interval {
     type Output = Self;
 
     fn neg(self) -> Self {
-        Interval(-self.0)
+        self.checked_neg()
+            .expect("interval negation left the Interval range")
     }
 }
 
@@ -75,7 +155,7 @@ pub mod interval_constants {
     pub const MAJ_2: Interval = Interval(2);
     pub const MIN_3: Interval = Interval(3);
     pub const MAJ_3: Interval = Interval(4);
-    pub const DIM_4: Interval = Interval(5);
+    pub const DIM_4: Interval = Interval(4);
     pub const PER_4: Interval = Interval(5);
     pub const AUG_4: Interval = Interval(6);
     pub const DIM_5: Interval = Interval(6);
@@ -101,3 +181,35 @@ pub mod interval_constants {
     pub const MAJ_14: Interval = Interval(23);
     pub const PER_15: Interval = Interval(24);
 }
+
+/******************************************************************************
+* TESTS
+******************************************************************************/
+
+#[cfg(test)]
+mod tests {
+    use super::super::{note_constants::*, Note};
+    use super::interval_constants::*;
+    use super::*;
+
+    #[test]
+    fn arithmetic_at_the_edges_of_the_range() {
+        let widest = Interval::new(i8::MIN);
+        assert_eq!(PER_15.checked_mul(5), Some(Interval::new(120)));
+        assert_eq!(PER_15.checked_mul(10), None);
+        assert_eq!(PER_15.saturating_mul(10), Interval::new(i8::MAX));
+        assert_eq!(widest.checked_neg(), None);
+        assert_eq!(widest.saturating_neg(), Interval::new(i8::MAX));
+        assert_eq!(widest.checked_sub(MIN_2), None);
+        assert_eq!(widest.saturating_sub(MIN_2), widest);
+        assert_eq!(PER_15.checked_add(PER_15), Some(Interval::new(48)));
+        assert_eq!(C4.saturating_sub(widest), Note::try_new(127).unwrap());
+        assert_eq!(C4.saturating_add(widest), Note::try_new(0).unwrap());
+    }
+
+    #[test]
+    #[should_panic(expected = "interval multiplication left the Interval range")]
+    fn multiplying_out_of_range() {
+        let _ = "P15".parse::<Interval>().unwrap() * 10;
+    }
+}
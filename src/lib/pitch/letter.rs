@@ -0,0 +1,71 @@
+use super::{Letter, DIATONIC_COUNT};
+
+impl Letter {
+    pub const ALL: [Letter; DIATONIC_COUNT] = [
+        Letter::C,
+        Letter::D,
+        Letter::E,
+        Letter::F,
+        Letter::G,
+        Letter::A,
+        Letter::B,
+    ];
+
+    /// Wraps around, so `from_index(7)` is `C` again.
+    pub fn from_index(i: i8) -> Letter {
+        Letter::ALL[i.rem_euclid(DIATONIC_COUNT as i8) as usize]
+    }
+
+    pub fn index(self) -> i8 {
+        self as i8
+    }
+
+    /// Semitones above `C` of the natural note.
+    pub fn semitones(self) -> i8 {
+        match self {
+            Letter::C => 0,
+            Letter::D => 2,
+            Letter::E => 4,
+            Letter::F => 5,
+            Letter::G => 7,
+            Letter::A => 9,
+            Letter::B => 11,
+        }
+    }
+
+    /// Moves `steps` letters up (or down when negative), e.g. `C` offset by 2 is `E`.
+    pub fn offset(self, steps: i8) -> Letter {
+        Letter::from_index(self.index() + steps)
+    }
+
+    pub fn from_char(c: char) -> Option<Letter> {
+        match c.to_ascii_uppercase() {
+            'C' => Some(Letter::C),
+            'D' => Some(Letter::D),
+            'E' => Some(Letter::E),
+            'F' => Some(Letter::F),
+            'G' => Some(Letter::G),
+            'A' => Some(Letter::A),
+            'B' => Some(Letter::B),
+            _ => None,
+        }
+    }
+
+    pub fn to_char(self) -> char {
+        match self {
+            Letter::C => 'C',
+            Letter::D => 'D',
+            Letter::E => 'E',
+            Letter::F => 'F',
+            Letter::G => 'G',
+            Letter::A => 'A',
+            Letter::B => 'B',
+        }
+    }
+}
+
+impl std::fmt::Display for Letter {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.to_char())
+    }
+}
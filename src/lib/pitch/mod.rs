@@ -1,16 +1,27 @@
+mod accidental;
 mod chromatic_range;
 mod degree;
 mod interval;
+mod letter;
 mod note;
 mod pitch_class;
+mod pitch_class_set;
 mod scale_family;
+mod spelled_interval;
+mod spelled_pitch;
+mod tone_row;
 
+pub use accidental::*;
 pub use chromatic_range::*;
 pub use degree::*;
 pub use interval::*;
 pub use note::*;
 pub use pitch_class::*;
+pub use pitch_class_set::*;
 pub use scale_family::*;
+pub use spelled_interval::*;
+pub use spelled_pitch::*;
+pub use tone_row::*;
 
 pub const DIATONIC_COUNT: usize = 7;
 pub const CHROMATIC_COUNT: usize = 12;
@@ -32,15 +43,22 @@ pub const AEOLIAN: usize = 5;
 pub const LOCRIAN: usize = 6;
 
 /// https://en.wikipedia.org/wiki/Degree_(music)
-#[derive(Clone, Copy, Default, Debug, PartialEq, PartialOrd)]
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Degree(i8);
 
+/// A degree raised or lowered chromatically, e.g. the `bVII` borrowed from the parallel minor.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, Hash)]
+pub struct AlteredDegree {
+    accidental: Accidental,
+    degree: Degree,
+}
+
 /// https://en.wikipedia.org/wiki/Interval_(music)
-#[derive(Clone, Copy, Default, Debug, PartialEq, PartialOrd)]
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Interval(i8);
 
 /// https://en.wikipedia.org/wiki/Musical_note
-#[derive(Clone, Copy, Default, Debug, PartialEq, PartialOrd)]
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Note(i8);
 
 /// https://en.wikipedia.org/wiki/Range_(music)
@@ -52,9 +70,97 @@ pub struct ChromaticRange {
 }
 
 /// https://en.wikipedia.org/wiki/Pitch_class
-#[derive(Clone, Copy, Default, Debug, PartialEq, PartialOrd)]
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct PitchClass(i8);
 
+/// https://en.wikipedia.org/wiki/Set_theory_(music)
+///
+/// Bit `i` is set when pitch class `i` is a member.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PitchClassSet(u16);
+
+/// https://en.wikipedia.org/wiki/Forte_number
+///
+/// Prime forms follow Rahn, which differs from Forte's own for a handful of set classes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ForteNumber {
+    cardinality: u8,
+    ordinal: u8,
+    z: bool,
+}
+
+/// https://en.wikipedia.org/wiki/Tone_row
+///
+/// Holds each of the twelve pitch classes exactly once.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ToneRow([PitchClass; CHROMATIC_COUNT]);
+
+/// https://en.wikipedia.org/wiki/Twelve-tone_technique#Tone_row
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RowForm {
+    Prime,
+    Retrograde,
+    Inversion,
+    RetrogradeInversion,
+}
+
+/// https://en.wikipedia.org/wiki/Letter_notation
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Letter {
+    C,
+    D,
+    E,
+    F,
+    G,
+    A,
+    B,
+}
+
+/// https://en.wikipedia.org/wiki/Accidental_(music)
+///
+/// Counts sharps when positive and flats when negative.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Accidental(i8);
+
+/// A pitch class together with how it is spelled, e.g. `C#` as opposed to `Db`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SpelledPitchClass {
+    letter: Letter,
+    accidental: Accidental,
+}
+
+/// https://en.wikipedia.org/wiki/Scientific_pitch_notation
+///
+/// The octave belongs to the letter, so `B#3` sounds as `C4` and `Cb4` as `B3`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SpelledPitch {
+    letter: Letter,
+    accidental: Accidental,
+    octave: i8,
+}
+
+/// https://en.wikipedia.org/wiki/Interval_(music)#Quality
+///
+/// Diminished and augmented carry how many times over they apply, e.g. `Augmented(2)` is doubly augmented.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum IntervalQuality {
+    Diminished(u8),
+    Minor,
+    Perfect,
+    Major,
+    Augmented(u8),
+}
+
+/// An interval with a generic number and a quality, e.g. a diminished fifth as opposed to an augmented fourth.
+///
+/// The number is 1 for a unison, 2 for a second, ..., 8 for an octave, 9 for a ninth and so on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SpelledInterval {
+    quality: IntervalQuality,
+    number: u8,
+    descending: bool,
+}
+
 pub struct ScaleFamily {
     /// Transposed interval stacks.
     pub modes: Vec<Vec<Interval>>,
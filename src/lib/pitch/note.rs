@@ -1,8 +1,60 @@
-use super::{Interval, Note};
+use super::note_constants::MIDI_NOTE_COUNT;
+use super::{Interval, Note, ParsePitchError, PitchClass, SpelledPitch, CHROMATIC_COUNT};
 
 impl Note {
+    /// Panics outside of the MIDI note range, see `try_new` for a fallible alternative.
     pub fn new(x: i8) -> Note {
-        Note(x)
+        Note::try_new(x).expect("note outside of the MIDI note range")
+    }
+
+    pub fn try_new(x: i8) -> Option<Note> {
+        if x >= 0 && (x as usize) < MIDI_NOTE_COUNT {
+            Some(Note(x))
+        } else {
+            None
+        }
+    }
+
+    /// Returns `None` outside of the MIDI note range.
+    pub fn from_pitch_class(pc: PitchClass, octave: i8) -> Option<Note> {
+        Note::try_new_wide((octave as i16 + 1) * CHROMATIC_COUNT as i16 + pc.0 as i16)
+    }
+
+    pub fn pitch_class(self) -> PitchClass {
+        PitchClass(self.0.rem_euclid(CHROMATIC_COUNT as i8))
+    }
+
+    /// Scientific pitch notation octave, so `C4` is in octave 4 and `Csub1` in octave -1.
+    pub fn octave(self) -> i8 {
+        self.0.div_euclid(CHROMATIC_COUNT as i8) - 1
+    }
+
+    pub fn checked_add(self, i: Interval) -> Option<Note> {
+        Note::try_new_wide(self.0 as i16 + i.0 as i16)
+    }
+
+    pub fn checked_sub(self, i: Interval) -> Option<Note> {
+        Note::try_new_wide(self.0 as i16 - i.0 as i16)
+    }
+
+    /// Stops at the edges of the MIDI note range.
+    pub fn saturating_add(self, i: Interval) -> Note {
+        let x = self.0 as i16 + i.0 as i16;
+        Note(x.max(0).min(MIDI_NOTE_COUNT as i16 - 1) as i8)
+    }
+
+    /// Stops at the edges of the MIDI note range.
+    pub fn saturating_sub(self, i: Interval) -> Note {
+        let x = self.0 as i16 - i.0 as i16;
+        Note(x.max(0).min(MIDI_NOTE_COUNT as i16 - 1) as i8)
+    }
+
+    fn try_new_wide(x: i16) -> Option<Note> {
+        if x >= 0 && (x as usize) < MIDI_NOTE_COUNT {
+            Some(Note(x as i8))
+        } else {
+            None
+        }
     }
 
     pub fn pitch(&self) -> f64 {
@@ -24,19 +76,61 @@ impl From<Note> for usize {
     }
 }
 
+/// Prints black keys as sharps, e.g. `C#4`.
+impl std::fmt::Display for Note {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        SpelledPitch::sharp(*self).fmt(f)
+    }
+}
+
+/// Parses any spelling within the MIDI note range, e.g. `Db4` or `B#3`.
+impl std::str::FromStr for Note {
+    type Err = ParsePitchError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse::<SpelledPitch>()?
+            .note()
+            .ok_or(ParsePitchError::OutOfRange)
+    }
+}
+
+/// Panics when leaving the MIDI note range, like integer overflow does.
 impl std::ops::Add<Interval> for Note {
     type Output = Self;
 
     fn add(self, other: Interval) -> Self {
-        Note(self.0 + other.0)
+        self.checked_add(other)
+            .expect("note addition left the MIDI note range")
     }
 }
 
+/// Panics when leaving the MIDI note range, like integer overflow does.
 impl std::ops::Sub<Interval> for Note {
     type Output = Self;
 
     fn sub(self, other: Interval) -> Self {
-        Note(self.0 - other.0)
+        self.checked_sub(other)
+            .expect("note subtraction left the MIDI note range")
+    }
+}
+
+impl std::ops::Sub<Note> for Note {
+    type Output = Interval;
+
+    fn sub(self, other: Note) -> Interval {
+        Interval(self.0 - other.0)
+    }
+}
+
+impl std::ops::AddAssign<Interval> for Note {
+    fn add_assign(&mut self, x: Interval) {
+        *self = *self + x;
+    }
+}
+
+impl std::ops::SubAssign<Interval> for Note {
+    fn sub_assign(&mut self, x: Interval) {
+        *self = *self - x;
     }
 }
 
@@ -1,3 +1,80 @@
+use super::{Interval, ParsePitchError, PitchClass, SpelledPitchClass, CHROMATIC_COUNT};
+
+impl PitchClass {
+    /// Wraps around, so `PitchClass::new(13)` is `Cs`.
+    pub fn new(x: i8) -> PitchClass {
+        PitchClass(x.rem_euclid(CHROMATIC_COUNT as i8))
+    }
+
+    /// `0` to `9`, then `T` and `E` for ten and eleven.
+    pub fn integer_notation(self) -> char {
+        match self.0 {
+            10 => 'T',
+            11 => 'E',
+            x => (b'0' + x as u8) as char,
+        }
+    }
+
+    /// The ascending interval up to `other`, within an octave.
+    pub fn interval_to(self, other: PitchClass) -> Interval {
+        other - self
+    }
+}
+
+impl From<PitchClass> for usize {
+    fn from(x: PitchClass) -> usize {
+        x.0 as usize
+    }
+}
+
+/// Prints black keys as sharps, e.g. `C#`.
+impl std::fmt::Display for PitchClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        SpelledPitchClass::sharp(*self).fmt(f)
+    }
+}
+
+/// Parses any spelling, e.g. `Db`, `C#` or `B##`.
+impl std::str::FromStr for PitchClass {
+    type Err = ParsePitchError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse::<SpelledPitchClass>()
+            .map(SpelledPitchClass::pitch_class)
+    }
+}
+
+impl std::ops::Add<Interval> for PitchClass {
+    type Output = Self;
+
+    fn add(self, other: Interval) -> Self {
+        PitchClass((self.0 as i16 + other.0 as i16).rem_euclid(CHROMATIC_COUNT as i16) as i8)
+    }
+}
+
+impl std::ops::Sub<Interval> for PitchClass {
+    type Output = Self;
+
+    fn sub(self, other: Interval) -> Self {
+        PitchClass((self.0 as i16 - other.0 as i16).rem_euclid(CHROMATIC_COUNT as i16) as i8)
+    }
+}
+
+/// The ascending interval from `other` up to `self`, within an octave.
+impl std::ops::Sub<PitchClass> for PitchClass {
+    type Output = Interval;
+
+    fn sub(self, other: PitchClass) -> Interval {
+        Interval((self.0 - other.0).rem_euclid(CHROMATIC_COUNT as i8))
+    }
+}
+
+impl std::ops::AddAssign<Interval> for PitchClass {
+    fn add_assign(&mut self, x: Interval) {
+        *self = *self + x;
+    }
+}
+
 pub mod consts {
     use super::super::PitchClass;
 
@@ -0,0 +1,475 @@
+use super::{ForteNumber, Interval, Note, PitchClass, PitchClassSet, CHROMATIC_COUNT};
+
+const ALL_BITS: u16 = (1 << CHROMATIC_COUNT) - 1;
+
+/******************************************************************************
+* ERRORS
+******************************************************************************/
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseForteNumberError(String);
+
+impl std::fmt::Display for ParseForteNumberError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "invalid Forte number \"{}\"", self.0)
+    }
+}
+
+impl std::error::Error for ParseForteNumberError {}
+
+/******************************************************************************
+* IMPLS
+******************************************************************************/
+
+impl PitchClassSet {
+    pub fn new() -> PitchClassSet {
+        PitchClassSet(0)
+    }
+
+    /// Bits above the twelfth are ignored.
+    pub fn from_bits(bits: u16) -> PitchClassSet {
+        PitchClassSet(bits & ALL_BITS)
+    }
+
+    pub fn chromatic() -> PitchClassSet {
+        PitchClassSet(ALL_BITS)
+    }
+
+    pub fn from_notes(notes: &[Note]) -> PitchClassSet {
+        notes.iter().map(|n| n.pitch_class()).collect()
+    }
+
+    pub fn bits(self) -> u16 {
+        self.0
+    }
+
+    pub fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn contains(self, pc: PitchClass) -> bool {
+        self.0 & (1 << pc.0) != 0
+    }
+
+    pub fn insert(&mut self, pc: PitchClass) {
+        self.0 |= 1 << pc.0;
+    }
+
+    pub fn remove(&mut self, pc: PitchClass) {
+        self.0 &= !(1 << pc.0);
+    }
+
+    /// Members in ascending order from `C`.
+    pub fn iter(self) -> impl Iterator<Item = PitchClass> {
+        (0..CHROMATIC_COUNT as i8)
+            .filter(move |&i| self.0 & (1 << i) != 0)
+            .map(PitchClass)
+    }
+
+    pub fn is_subset(self, other: PitchClassSet) -> bool {
+        self.0 & !other.0 == 0
+    }
+
+    /// Tn
+    pub fn transpose(self, interval: Interval) -> PitchClassSet {
+        let n = interval.0.rem_euclid(CHROMATIC_COUNT as i8) as u16;
+        PitchClassSet(((self.0 << n) | (self.0 >> (CHROMATIC_COUNT as u16 - n))) & ALL_BITS)
+    }
+
+    /// I, i.e. inversion around `C`. Compose with `transpose` for TnI.
+    pub fn invert(self) -> PitchClassSet {
+        self.iter()
+            .map(|pc| PitchClass((-pc.0).rem_euclid(CHROMATIC_COUNT as i8)))
+            .collect()
+    }
+
+    pub fn complement(self) -> PitchClassSet {
+        PitchClassSet(!self.0 & ALL_BITS)
+    }
+
+    /// The rotation of the members that is most packed to the left, as described by Rahn.
+    ///
+    /// The span is minimised first, then the interval from the first member to the second to last
+    /// and so on. Symmetrical sets start on their lowest member.
+    pub fn normal_order(self) -> Vec<PitchClass> {
+        let start = self
+            .iter()
+            .min_by_key(|&pc| self.transpose(-Interval(pc.0)).0)
+            .unwrap_or_default();
+        let mut members: Vec<PitchClass> = self.iter().collect();
+        let start_ix = members.iter().position(|&pc| pc == start).unwrap_or(0);
+        members.rotate_left(start_ix);
+        members
+    }
+
+    /// The most packed form among all transpositions and inversions, transposed to start on `C`.
+    pub fn prime_form(self) -> PitchClassSet {
+        let inverted = self.invert();
+        self.iter()
+            .map(|pc| self.transpose(-Interval(pc.0)))
+            .chain(
+                inverted
+                    .iter()
+                    .map(|pc| inverted.transpose(-Interval(pc.0))),
+            )
+            .min_by_key(|set| set.0)
+            .unwrap_or_default()
+    }
+
+    /// Whether `other` can be reached by transposition and/or inversion.
+    pub fn is_same_set_class(self, other: PitchClassSet) -> bool {
+        self.prime_form() == other.prime_form()
+    }
+
+    /// How many times each interval class from 1 to 6 occurs between pairs of members.
+    pub fn interval_vector(self) -> [u8; 6] {
+        let mut vector = [0; 6];
+        let members: Vec<PitchClass> = self.iter().collect();
+        for (i, &a) in members.iter().enumerate() {
+            for &b in &members[i + 1..] {
+                let ic = (b - a).0.min(CHROMATIC_COUNT as i8 - (b - a).0);
+                vector[ic as usize - 1] += 1;
+            }
+        }
+        vector
+    }
+
+    /// Whether the sets share an interval vector without being in the same set class.
+    pub fn is_z_related(self, other: PitchClassSet) -> bool {
+        self.interval_vector() == other.interval_vector() && !self.is_same_set_class(other)
+    }
+
+    /// The prime form sharing the interval vector of `self` from another set class, if any.
+    pub fn z_partner(self) -> Option<PitchClassSet> {
+        let prime = self.prime_form();
+        let vector = self.interval_vector();
+        (0..=ALL_BITS)
+            .map(PitchClassSet)
+            .filter(|set| set.len() == self.len() && set.0 & 1 != 0)
+            .find(|&set| set.prime_form() == set && set != prime && set.interval_vector() == vector)
+    }
+
+    pub fn forte_number(self) -> ForteNumber {
+        let prime = self.prime_form();
+        let cardinality = prime.len() as u8;
+        if cardinality as usize > CHROMATIC_COUNT / 2 {
+            return ForteNumber {
+                cardinality,
+                ..prime.complement().forte_number()
+            };
+        }
+        FORTE_TABLE
+            .iter()
+            .find(|(_, members)| parse_members(members) == prime)
+            .map(|(name, _)| name.parse().unwrap())
+            .expect("every prime form up to the hexachords is listed")
+    }
+
+    /// The prime form of the set class named by `number`, if there is one.
+    pub fn from_forte_number(number: ForteNumber) -> Option<PitchClassSet> {
+        if number.cardinality as usize > CHROMATIC_COUNT / 2 {
+            let complement = ForteNumber {
+                cardinality: CHROMATIC_COUNT as u8 - number.cardinality,
+                ..number
+            };
+            return PitchClassSet::from_forte_number(complement)
+                .map(|set| set.complement().prime_form());
+        }
+        FORTE_TABLE
+            .iter()
+            .find(|(name, _)| name.parse() == Ok(number))
+            .map(|(_, members)| parse_members(members))
+    }
+}
+
+impl ForteNumber {
+    pub fn cardinality(self) -> u8 {
+        self.cardinality
+    }
+
+    pub fn ordinal(self) -> u8 {
+        self.ordinal
+    }
+
+    /// Whether the set class has a Z-related partner.
+    pub fn is_z(self) -> bool {
+        self.z
+    }
+}
+
+impl std::iter::FromIterator<PitchClass> for PitchClassSet {
+    fn from_iter<I: IntoIterator<Item = PitchClass>>(iter: I) -> Self {
+        let mut set = PitchClassSet::new();
+        for pc in iter {
+            set.insert(pc);
+        }
+        set
+    }
+}
+
+impl std::ops::BitOr for PitchClassSet {
+    type Output = Self;
+
+    fn bitor(self, other: PitchClassSet) -> Self {
+        PitchClassSet(self.0 | other.0)
+    }
+}
+
+impl std::ops::BitAnd for PitchClassSet {
+    type Output = Self;
+
+    fn bitand(self, other: PitchClassSet) -> Self {
+        PitchClassSet(self.0 & other.0)
+    }
+}
+
+/// Prints integer notation with `T` and `E` for ten and eleven, e.g. `{0,4,7}`.
+impl std::fmt::Display for PitchClassSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("{")?;
+        for (i, pc) in self.iter().enumerate() {
+            if i > 0 {
+                f.write_str(",")?;
+            }
+            write!(f, "{}", pc.integer_notation())?;
+        }
+        f.write_str("}")
+    }
+}
+
+/// Prints e.g. `4-Z15`.
+impl std::fmt::Display for ForteNumber {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let z = if self.z { "Z" } else { "" };
+        write!(f, "{}-{}{}", self.cardinality, z, self.ordinal)
+    }
+}
+
+impl std::str::FromStr for ForteNumber {
+    type Err = ParseForteNumberError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseForteNumberError(s.to_string());
+        let mut parts = s.splitn(2, '-');
+        let cardinality = parts
+            .next()
+            .and_then(|c| c.parse::<u8>().ok())
+            .filter(|&c| c as usize <= CHROMATIC_COUNT)
+            .ok_or_else(err)?;
+        let rest = parts.next().ok_or_else(err)?;
+        let (z, ordinal) = match rest.strip_prefix('Z').or_else(|| rest.strip_prefix('z')) {
+            Some(ordinal) => (true, ordinal),
+            None => (false, rest),
+        };
+        let ordinal = ordinal
+            .parse::<u8>()
+            .ok()
+            .filter(|&o| o > 0)
+            .ok_or_else(err)?;
+        Ok(ForteNumber {
+            cardinality,
+            ordinal,
+            z,
+        })
+    }
+}
+
+fn parse_members(members: &str) -> PitchClassSet {
+    members
+        .chars()
+        .map(|c| match c {
+            'T' => PitchClass(10),
+            'E' => PitchClass(11),
+            c => PitchClass(c.to_digit(10).unwrap() as i8),
+        })
+        .collect()
+}
+
+/// Set classes up to the hexachords, larger ones are numbered after their complements.
+const FORTE_TABLE: [(&str, &str); 137] = [
+    ("0-1", ""),
+    ("1-1", "0"),
+    ("2-1", "01"),
+    ("2-2", "02"),
+    ("2-3", "03"),
+    ("2-4", "04"),
+    ("2-5", "05"),
+    ("2-6", "06"),
+    ("3-1", "012"),
+    ("3-2", "013"),
+    ("3-3", "014"),
+    ("3-4", "015"),
+    ("3-5", "016"),
+    ("3-6", "024"),
+    ("3-7", "025"),
+    ("3-8", "026"),
+    ("3-9", "027"),
+    ("3-10", "036"),
+    ("3-11", "037"),
+    ("3-12", "048"),
+    ("4-1", "0123"),
+    ("4-2", "0124"),
+    ("4-3", "0134"),
+    ("4-4", "0125"),
+    ("4-5", "0126"),
+    ("4-6", "0127"),
+    ("4-7", "0145"),
+    ("4-8", "0156"),
+    ("4-9", "0167"),
+    ("4-10", "0235"),
+    ("4-11", "0135"),
+    ("4-12", "0236"),
+    ("4-13", "0136"),
+    ("4-14", "0237"),
+    ("4-Z15", "0146"),
+    ("4-16", "0157"),
+    ("4-17", "0347"),
+    ("4-18", "0147"),
+    ("4-19", "0148"),
+    ("4-20", "0158"),
+    ("4-21", "0246"),
+    ("4-22", "0247"),
+    ("4-23", "0257"),
+    ("4-24", "0248"),
+    ("4-25", "0268"),
+    ("4-26", "0358"),
+    ("4-27", "0258"),
+    ("4-28", "0369"),
+    ("4-Z29", "0137"),
+    ("5-1", "01234"),
+    ("5-2", "01235"),
+    ("5-3", "01245"),
+    ("5-4", "01236"),
+    ("5-5", "01237"),
+    ("5-6", "01256"),
+    ("5-7", "01267"),
+    ("5-8", "02346"),
+    ("5-9", "01246"),
+    ("5-10", "01346"),
+    ("5-11", "02347"),
+    ("5-Z12", "01356"),
+    ("5-13", "01248"),
+    ("5-14", "01257"),
+    ("5-15", "01268"),
+    ("5-16", "01347"),
+    ("5-Z17", "01348"),
+    ("5-Z18", "01457"),
+    ("5-19", "01367"),
+    ("5-20", "01568"),
+    ("5-21", "01458"),
+    ("5-22", "01478"),
+    ("5-23", "02357"),
+    ("5-24", "01357"),
+    ("5-25", "02358"),
+    ("5-26", "02458"),
+    ("5-27", "01358"),
+    ("5-28", "02368"),
+    ("5-29", "01368"),
+    ("5-30", "01468"),
+    ("5-31", "01369"),
+    ("5-32", "01469"),
+    ("5-33", "02468"),
+    ("5-34", "02469"),
+    ("5-35", "02479"),
+    ("5-Z36", "01247"),
+    ("5-Z37", "03458"),
+    ("5-Z38", "01258"),
+    ("6-1", "012345"),
+    ("6-2", "012346"),
+    ("6-Z3", "012356"),
+    ("6-Z4", "012456"),
+    ("6-5", "012367"),
+    ("6-Z6", "012567"),
+    ("6-7", "012678"),
+    ("6-8", "023457"),
+    ("6-9", "012357"),
+    ("6-Z10", "013457"),
+    ("6-Z11", "012457"),
+    ("6-Z12", "012467"),
+    ("6-Z13", "013467"),
+    ("6-14", "013458"),
+    ("6-15", "012458"),
+    ("6-16", "014568"),
+    ("6-Z17", "012478"),
+    ("6-18", "012578"),
+    ("6-Z19", "013478"),
+    ("6-20", "014589"),
+    ("6-21", "023468"),
+    ("6-22", "012468"),
+    ("6-Z23", "023568"),
+    ("6-Z24", "013468"),
+    ("6-Z25", "013568"),
+    ("6-Z26", "013578"),
+    ("6-27", "013469"),
+    ("6-Z28", "013569"),
+    ("6-Z29", "023679"),
+    ("6-30", "013679"),
+    ("6-31", "014579"),
+    ("6-32", "024579"),
+    ("6-33", "023579"),
+    ("6-34", "013579"),
+    ("6-35", "02468T"),
+    ("6-Z36", "012347"),
+    ("6-Z37", "012348"),
+    ("6-Z38", "012378"),
+    ("6-Z39", "023458"),
+    ("6-Z40", "012358"),
+    ("6-Z41", "012368"),
+    ("6-Z42", "012369"),
+    ("6-Z43", "012568"),
+    ("6-Z44", "012569"),
+    ("6-Z45", "023469"),
+    ("6-Z46", "012469"),
+    ("6-Z47", "012479"),
+    ("6-Z48", "012579"),
+    ("6-Z49", "013479"),
+    ("6-Z50", "014679"),
+];
+
+/******************************************************************************
+* TESTS
+******************************************************************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(members: &str) -> PitchClassSet {
+        parse_members(members)
+    }
+
+    #[test]
+    fn forte_numbers_of_known_sets() {
+        assert_eq!(set("047").forte_number().to_string(), "3-11");
+        assert_eq!(set("0146").forte_number().to_string(), "4-Z15");
+        assert_eq!(set("02479").forte_number().to_string(), "5-35");
+        assert_eq!(set("024579E").forte_number().to_string(), "7-35");
+        assert_eq!(set("047").interval_vector(), [0, 0, 1, 1, 1, 0]);
+    }
+
+    #[test]
+    fn forte_numbers_round_trip_for_every_set_class() {
+        for bits in 0..=ALL_BITS {
+            let prime = PitchClassSet(bits).prime_form();
+            let number = prime.forte_number();
+            assert_eq!(number.to_string().parse(), Ok(number), "{}", prime);
+            assert_eq!(PitchClassSet::from_forte_number(number), Some(prime));
+            assert_eq!(number.is_z(), prime.z_partner().is_some(), "{}", number);
+        }
+    }
+
+    #[test]
+    fn invalid_forte_numbers() {
+        for s in ["", "3", "3-", "3-0", "13-1", "x-1", "3-Zx"].iter() {
+            assert!(s.parse::<ForteNumber>().is_err(), "{}", s);
+        }
+        assert_eq!(
+            PitchClassSet::from_forte_number("3-13".parse().unwrap()),
+            None
+        );
+    }
+}
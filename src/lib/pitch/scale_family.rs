@@ -1,6 +1,6 @@
 use super::interval_constants::{AUG_5, DIM_5, MIN_3};
-use super::{Interval, ScaleFamily};
-use super::{DIATONIC_COUNT, FIFTH, THIRD};
+use super::{Degree, Interval, Note, ScaleFamily, SpelledPitch, SpelledPitchClass};
+use super::{CHROMATIC_COUNT, DIATONIC_COUNT, FIFTH, THIRD};
 
 impl ScaleFamily {
     pub fn new(intervals: &[Interval], names: Vec<String>) -> ScaleFamily {
@@ -66,7 +66,7 @@ impl ScaleFamily {
             roman_name.push('b');
         }
 
-        roman_name.push_str(&roman_num(scale_degree + 1));
+        roman_name.push_str(&Degree(scale_degree as i8).to_string());
 
         let chord_degree = (mode + scale_degree) % DIATONIC_COUNT;
         let chord_scale = &self.modes[chord_degree];
@@ -85,17 +85,16 @@ impl ScaleFamily {
     pub fn chord_scale_0<'a>(&'a self, mode: usize, scale_degree: usize) -> &'a Vec<Interval> {
         &self.modes[(mode + scale_degree) % DIATONIC_COUNT]
     }
-}
 
-fn roman_num(mut n: usize) -> String {
-    let mut result = String::new();
-    for &(name, value) in [("X", 10), ("IX", 9), ("V", 5), ("IV", 4), ("I", 1)].iter() {
-        while n >= value {
-            n -= value;
-            result.push_str(name);
-        }
+    /// Spells the degrees of `mode` starting on `tonic`, one letter each.
+    pub fn spelled_scale(&self, mode: usize, tonic: SpelledPitchClass) -> Vec<SpelledPitchClass> {
+        tonic.spell_scale(&self.modes[mode])
+    }
+
+    /// Spells `note` as it would be written in `mode` on `tonic`.
+    pub fn spell(&self, mode: usize, tonic: SpelledPitchClass, note: Note) -> SpelledPitch {
+        SpelledPitch::in_scale(note, tonic, &self.modes[mode])
     }
-    result
 }
 
 fn calc_modes(scale: &[Interval]) -> Vec<Vec<Interval>> {
@@ -104,22 +103,40 @@ fn calc_modes(scale: &[Interval]) -> Vec<Vec<Interval>> {
         .enumerate()
         .map(|(i, &offset)| {
             (i..i + scale.len())
-                .map(|x| (scale[x % scale.len()] - offset).positive_less_than(DIATONIC_COUNT as i8))
+                .map(|x| {
+                    (scale[x % scale.len()] - offset).positive_less_than(CHROMATIC_COUNT as i8)
+                })
                 .collect()
         })
         .collect()
 }
 
 pub mod scale_family_constants {
-    use super::super::interval_constants::{
-        DIM_4, DIM_5, MAJ_2, MAJ_3, MAJ_6, MAJ_7, PER_1, PER_4, PER_5,
+    use super::super::spelled_interval_constants::{
+        AUG_4, AUG_5, MAJ_2, MAJ_3, MAJ_6, MAJ_7, PER_1, PER_4, PER_5,
     };
-    use super::super::{Interval, DIATONIC_COUNT};
+    use super::super::{Interval, SpelledInterval, DIATONIC_COUNT};
 
-    pub const MAJOR_FAMILY: [Interval; DIATONIC_COUNT] =
+    pub const MAJOR_FAMILY_SPELLED: [SpelledInterval; DIATONIC_COUNT] =
         [PER_1, MAJ_2, MAJ_3, PER_4, PER_5, MAJ_6, MAJ_7];
+    pub const HARMONIC_MINOR_FAMILY_SPELLED: [SpelledInterval; DIATONIC_COUNT] =
+        [PER_1, MAJ_2, MAJ_3, PER_4, AUG_5, MAJ_6, MAJ_7];
+    pub const MELODIC_MINOR_FAMILY_SPELLED: [SpelledInterval; DIATONIC_COUNT] =
+        [PER_1, MAJ_2, MAJ_3, AUG_4, AUG_5, MAJ_6, MAJ_7];
+
+    pub const MAJOR_FAMILY: [Interval; DIATONIC_COUNT] = semitones(MAJOR_FAMILY_SPELLED);
     pub const HARMONIC_MINOR_FAMILY: [Interval; DIATONIC_COUNT] =
-        [PER_1, MAJ_2, MAJ_3, PER_4, DIM_5, MAJ_6, MAJ_7];
+        semitones(HARMONIC_MINOR_FAMILY_SPELLED);
     pub const MELODIC_MINOR_FAMILY: [Interval; DIATONIC_COUNT] =
-        [PER_1, MAJ_2, MAJ_3, DIM_4, DIM_5, MAJ_6, MAJ_7];
+        semitones(MELODIC_MINOR_FAMILY_SPELLED);
+
+    const fn semitones(spelled: [SpelledInterval; DIATONIC_COUNT]) -> [Interval; DIATONIC_COUNT] {
+        let mut result = [Interval(0); DIATONIC_COUNT];
+        let mut i = 0;
+        while i < DIATONIC_COUNT {
+            result[i] = spelled[i].interval();
+            i += 1;
+        }
+        result
+    }
 }
@@ -0,0 +1,437 @@
+use std::convert::TryFrom;
+
+use super::{
+    Interval, IntervalQuality, SpelledInterval, SpelledPitch, SpelledPitchClass, CHROMATIC_COUNT,
+    DIATONIC_COUNT,
+};
+
+/// Semitones of the major and perfect intervals from a unison to a seventh.
+const MAJOR_SEMITONES: [i8; DIATONIC_COUNT] = [0, 2, 4, 5, 7, 9, 11];
+
+/******************************************************************************
+* ERRORS
+******************************************************************************/
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParseIntervalError {
+    Empty,
+    InvalidQuality(String),
+    InvalidNumber(String),
+    QualityMismatch(IntervalQuality, u8),
+}
+
+impl std::fmt::Display for ParseIntervalError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ParseIntervalError::Empty => write!(f, "expected an interval, found nothing"),
+            ParseIntervalError::InvalidQuality(s) => write!(
+                f,
+                "expected one of the qualities P, M, m, d or A, found \"{}\"",
+                s
+            ),
+            ParseIntervalError::InvalidNumber(s) => write!(f, "invalid interval number \"{}\"", s),
+            ParseIntervalError::QualityMismatch(quality, number) => write!(
+                f,
+                "quality {} does not apply to interval number {}",
+                quality, number
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParseIntervalError {}
+
+/******************************************************************************
+* IMPLS
+******************************************************************************/
+
+impl IntervalQuality {
+    /// Swaps major with minor and augmented with diminished, as happens on inversion.
+    pub fn inverted(self) -> IntervalQuality {
+        match self {
+            IntervalQuality::Diminished(k) => IntervalQuality::Augmented(k),
+            IntervalQuality::Minor => IntervalQuality::Major,
+            IntervalQuality::Perfect => IntervalQuality::Perfect,
+            IntervalQuality::Major => IntervalQuality::Minor,
+            IntervalQuality::Augmented(k) => IntervalQuality::Diminished(k),
+        }
+    }
+}
+
+impl SpelledInterval {
+    /// An ascending interval, or `None` if `quality` cannot apply to `number`, e.g. a major fifth,
+    /// or the interval spans too many semitones for an `Interval`.
+    pub fn new(quality: IntervalQuality, number: u8) -> Option<SpelledInterval> {
+        if number == 0 {
+            return None;
+        }
+        let perfect = is_perfect_number(number);
+        let valid = match quality {
+            IntervalQuality::Perfect => perfect,
+            IntervalQuality::Minor | IntervalQuality::Major => !perfect,
+            IntervalQuality::Diminished(k) | IntervalQuality::Augmented(k) => k > 0,
+        };
+        if !valid || !fits(quality, number) {
+            return None;
+        }
+        Some(SpelledInterval {
+            quality,
+            number,
+            descending: false,
+        })
+    }
+
+    /// Spells `interval` the most common way, preferring an augmented fourth for the tritone.
+    pub fn from_interval(interval: Interval) -> SpelledInterval {
+        let semitones = interval.0.unsigned_abs();
+        let octaves = semitones / CHROMATIC_COUNT as u8;
+        let (quality, number) = match semitones % CHROMATIC_COUNT as u8 {
+            0 => (IntervalQuality::Perfect, 1),
+            1 => (IntervalQuality::Minor, 2),
+            2 => (IntervalQuality::Major, 2),
+            3 => (IntervalQuality::Minor, 3),
+            4 => (IntervalQuality::Major, 3),
+            5 => (IntervalQuality::Perfect, 4),
+            6 => (IntervalQuality::Augmented(1), 4),
+            7 => (IntervalQuality::Perfect, 5),
+            8 => (IntervalQuality::Minor, 6),
+            9 => (IntervalQuality::Major, 6),
+            10 => (IntervalQuality::Minor, 7),
+            _ => (IntervalQuality::Major, 7),
+        };
+        SpelledInterval {
+            quality,
+            number: number + octaves * DIATONIC_COUNT as u8,
+            descending: interval.0 < 0,
+        }
+    }
+
+    /// The interval from `from` up (or down) to `to`, spelled as the letters have it.
+    pub fn between(from: SpelledPitch, to: SpelledPitch) -> SpelledInterval {
+        let steps = to.staff_position() - from.staff_position();
+        let semitones = to.midi_number() - from.midi_number();
+        let descending = steps < 0 || (steps == 0 && semitones < 0);
+        let interval = if descending {
+            SpelledInterval::with_number((1 - steps) as u8, Interval(-semitones as i8))
+        } else {
+            SpelledInterval::with_number((1 + steps) as u8, Interval(semitones as i8))
+        };
+        SpelledInterval {
+            descending,
+            ..interval
+        }
+    }
+
+    /// Spells the ascending `interval` as the generic `number`, whatever quality that takes,
+    /// e.g. six semitones as a fifth is a diminished fifth.
+    pub fn with_number(number: u8, interval: Interval) -> SpelledInterval {
+        assert!(number > 0);
+        let steps = number as i16 - 1;
+        let simple = (steps % DIATONIC_COUNT as i16) as usize;
+        let octaves = steps / DIATONIC_COUNT as i16;
+        let deviation =
+            interval.0 as i16 - MAJOR_SEMITONES[simple] as i16 - octaves * CHROMATIC_COUNT as i16;
+
+        let quality = if is_perfect_number(number) {
+            match deviation {
+                0 => IntervalQuality::Perfect,
+                d if d > 0 => IntervalQuality::Augmented(d as u8),
+                d => IntervalQuality::Diminished(-d as u8),
+            }
+        } else {
+            match deviation {
+                0 => IntervalQuality::Major,
+                -1 => IntervalQuality::Minor,
+                d if d > 0 => IntervalQuality::Augmented(d as u8),
+                d => IntervalQuality::Diminished((-d - 1) as u8),
+            }
+        };
+
+        SpelledInterval {
+            quality,
+            number,
+            descending: false,
+        }
+    }
+
+    /// The ascending simple interval from `from` to the next `to` above it.
+    pub fn between_classes(from: SpelledPitchClass, to: SpelledPitchClass) -> SpelledInterval {
+        let from = from.with_octave(4);
+        let mut to = to.with_octave(4);
+        if to.staff_position() < from.staff_position() {
+            to = to.class().with_octave(5);
+        }
+        SpelledInterval::between(from, to)
+    }
+
+    pub fn quality(self) -> IntervalQuality {
+        self.quality
+    }
+
+    /// The generic number, e.g. 3 for any kind of third.
+    pub fn number(self) -> u8 {
+        self.number
+    }
+
+    pub fn is_descending(self) -> bool {
+        self.descending
+    }
+
+    /// Steps on the staff, negative when descending.
+    pub fn steps(self) -> i8 {
+        let steps = self.number as i8 - 1;
+        if self.descending {
+            -steps
+        } else {
+            steps
+        }
+    }
+
+    /// Whether the interval spans more than an octave.
+    pub fn is_compound(self) -> bool {
+        self.number > 8
+    }
+
+    /// Whole octaves that `simple` strips off.
+    pub fn octaves(self) -> u8 {
+        if self.is_compound() {
+            (self.number - 2) / DIATONIC_COUNT as u8
+        } else {
+            0
+        }
+    }
+
+    /// Reduces a compound interval to within an octave, e.g. a major ninth to a major second.
+    pub fn simple(self) -> SpelledInterval {
+        SpelledInterval {
+            number: self.number - self.octaves() * DIATONIC_COUNT as u8,
+            ..self
+        }
+    }
+
+    /// Widens the interval by `octaves` octaves, e.g. a major second to a major ninth. Panics past
+    /// the semitones an `Interval` can span.
+    pub fn compound(self, octaves: u8) -> SpelledInterval {
+        let number = octaves
+            .checked_mul(DIATONIC_COUNT as u8)
+            .and_then(|octaves| self.number.checked_add(octaves))
+            .filter(|&number| fits(self.quality, number))
+            .expect("interval too wide");
+        SpelledInterval { number, ..self }
+    }
+
+    /// The simple interval that completes `self` to an octave, e.g. a minor third to a major sixth.
+    pub fn inverted(self) -> SpelledInterval {
+        SpelledInterval {
+            quality: self.quality.inverted(),
+            number: 9 - self.simple().number,
+            descending: self.descending,
+        }
+    }
+
+    // TODO: move constness into From impl https://github.com/rust-lang/rfcs/pull/2632
+    pub const fn interval(self) -> Interval {
+        // Construction keeps the span within an i8.
+        let semitones = span(self.quality, self.number) as i8;
+        Interval(if self.descending {
+            -semitones
+        } else {
+            semitones
+        })
+    }
+}
+
+impl From<SpelledInterval> for Interval {
+    fn from(x: SpelledInterval) -> Interval {
+        x.interval()
+    }
+}
+
+impl std::fmt::Display for IntervalQuality {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let (symbol, count) = match *self {
+            IntervalQuality::Diminished(k) => ("d", k),
+            IntervalQuality::Minor => ("m", 1),
+            IntervalQuality::Perfect => ("P", 1),
+            IntervalQuality::Major => ("M", 1),
+            IntervalQuality::Augmented(k) => ("A", k),
+        };
+        for _ in 0..count {
+            f.write_str(symbol)?;
+        }
+        Ok(())
+    }
+}
+
+/// Prints e.g. `m3`, `P5` or `-M2` for a descending major second.
+impl std::fmt::Display for SpelledInterval {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if self.descending {
+            f.write_str("-")?;
+        }
+        write!(f, "{}{}", self.quality, self.number)
+    }
+}
+
+impl std::str::FromStr for SpelledInterval {
+    type Err = ParseIntervalError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(ParseIntervalError::Empty);
+        }
+        let (descending, s) = match s.as_bytes()[0] {
+            b'-' => (true, &s[1..]),
+            b'+' => (false, &s[1..]),
+            _ => (false, s),
+        };
+
+        let digits = s.find(|c: char| c.is_ascii_digit()).unwrap_or(s.len());
+        let (quality_str, number_str) = s.split_at(digits);
+        let quality = match quality_str {
+            "P" => IntervalQuality::Perfect,
+            "M" => IntervalQuality::Major,
+            "m" => IntervalQuality::Minor,
+            q if !q.is_empty() && q.chars().all(|c| c == 'd') => IntervalQuality::Diminished(
+                u8::try_from(q.len())
+                    .map_err(|_| ParseIntervalError::InvalidQuality(q.to_string()))?,
+            ),
+            q if !q.is_empty() && q.chars().all(|c| c == 'A') => IntervalQuality::Augmented(
+                u8::try_from(q.len())
+                    .map_err(|_| ParseIntervalError::InvalidQuality(q.to_string()))?,
+            ),
+            q => return Err(ParseIntervalError::InvalidQuality(q.to_string())),
+        };
+        let number = number_str
+            .parse::<u8>()
+            .ok()
+            .filter(|&n| n > 0 && fits(quality, n))
+            .ok_or_else(|| ParseIntervalError::InvalidNumber(number_str.to_string()))?;
+
+        let interval = SpelledInterval::new(quality, number)
+            .ok_or(ParseIntervalError::QualityMismatch(quality, number))?;
+        Ok(if descending { -interval } else { interval })
+    }
+}
+
+impl std::ops::Neg for SpelledInterval {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        SpelledInterval {
+            descending: !self.descending,
+            ..self
+        }
+    }
+}
+
+/// Semitones of the ascending interval of `quality` and `number`, wide enough for any of them.
+const fn span(quality: IntervalQuality, number: u8) -> i16 {
+    let simple = ((number - 1) % DIATONIC_COUNT as u8) as usize;
+    let octaves = ((number - 1) / DIATONIC_COUNT as u8) as i16;
+    let offset = match quality {
+        IntervalQuality::Perfect | IntervalQuality::Major => 0,
+        IntervalQuality::Minor => -1,
+        IntervalQuality::Augmented(k) => k as i16,
+        IntervalQuality::Diminished(k) => {
+            if is_perfect_number(number) {
+                -(k as i16)
+            } else {
+                -(k as i16) - 1
+            }
+        }
+    };
+    MAJOR_SEMITONES[simple] as i16 + octaves * CHROMATIC_COUNT as i16 + offset
+}
+
+/// Whether the interval of `quality` and `number` spans few enough semitones for an `Interval`,
+/// either way.
+fn fits(quality: IntervalQuality, number: u8) -> bool {
+    span(quality, number).abs() <= i8::MAX as i16
+}
+
+/// Unisons, fourths, fifths and their compounds are perfect rather than major or minor.
+const fn is_perfect_number(number: u8) -> bool {
+    let simple = (number - 1) % DIATONIC_COUNT as u8;
+    simple == 0 || simple == 3 || simple == 4
+}
+
+pub mod spelled_interval_constants {
+    use super::super::IntervalQuality::{Augmented, Diminished, Major, Minor, Perfect};
+    use super::SpelledInterval;
+
+    const fn asc(quality: super::super::IntervalQuality, number: u8) -> SpelledInterval {
+        SpelledInterval {
+            quality,
+            number,
+            descending: false,
+        }
+    }
+
+    pub const PER_1: SpelledInterval = asc(Perfect, 1);
+    pub const AUG_1: SpelledInterval = asc(Augmented(1), 1);
+    pub const MIN_2: SpelledInterval = asc(Minor, 2);
+    pub const MAJ_2: SpelledInterval = asc(Major, 2);
+    pub const AUG_2: SpelledInterval = asc(Augmented(1), 2);
+    pub const DIM_3: SpelledInterval = asc(Diminished(1), 3);
+    pub const MIN_3: SpelledInterval = asc(Minor, 3);
+    pub const MAJ_3: SpelledInterval = asc(Major, 3);
+    pub const DIM_4: SpelledInterval = asc(Diminished(1), 4);
+    pub const PER_4: SpelledInterval = asc(Perfect, 4);
+    pub const AUG_4: SpelledInterval = asc(Augmented(1), 4);
+    pub const DIM_5: SpelledInterval = asc(Diminished(1), 5);
+    pub const PER_5: SpelledInterval = asc(Perfect, 5);
+    pub const AUG_5: SpelledInterval = asc(Augmented(1), 5);
+    pub const MIN_6: SpelledInterval = asc(Minor, 6);
+    pub const MAJ_6: SpelledInterval = asc(Major, 6);
+    pub const AUG_6: SpelledInterval = asc(Augmented(1), 6);
+    pub const DIM_7: SpelledInterval = asc(Diminished(1), 7);
+    pub const MIN_7: SpelledInterval = asc(Minor, 7);
+    pub const MAJ_7: SpelledInterval = asc(Major, 7);
+    pub const DIM_8: SpelledInterval = asc(Diminished(1), 8);
+    pub const PER_8: SpelledInterval = asc(Perfect, 8);
+    pub const MIN_9: SpelledInterval = asc(Minor, 9);
+    pub const MAJ_9: SpelledInterval = asc(Major, 9);
+    pub const AUG_9: SpelledInterval = asc(Augmented(1), 9);
+    pub const MIN_10: SpelledInterval = asc(Minor, 10);
+    pub const MAJ_10: SpelledInterval = asc(Major, 10);
+    pub const PER_11: SpelledInterval = asc(Perfect, 11);
+    pub const AUG_11: SpelledInterval = asc(Augmented(1), 11);
+    pub const DIM_12: SpelledInterval = asc(Diminished(1), 12);
+    pub const PER_12: SpelledInterval = asc(Perfect, 12);
+    pub const AUG_12: SpelledInterval = asc(Augmented(1), 12);
+    pub const MIN_13: SpelledInterval = asc(Minor, 13);
+    pub const MAJ_13: SpelledInterval = asc(Major, 13);
+    pub const MIN_14: SpelledInterval = asc(Minor, 14);
+    pub const MAJ_14: SpelledInterval = asc(Major, 14);
+    pub const PER_15: SpelledInterval = asc(Perfect, 15);
+}
+
+/******************************************************************************
+* TESTS
+******************************************************************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn intervals_round_trip() {
+        for s in ["P1", "m2", "-M3", "A4", "d5", "P8", "dd7", "AAA4", "-P15"].iter() {
+            let interval: SpelledInterval = s.parse().unwrap();
+            assert_eq!(interval.to_string(), *s);
+        }
+    }
+
+    #[test]
+    fn invalid_intervals() {
+        for s in ["", "P", "3", "P3", "M5", "x2", "P0", "Ad3"].iter() {
+            assert!(s.parse::<SpelledInterval>().is_err(), "{}", s);
+        }
+        let quality = "d".repeat(256);
+        assert_eq!(
+            format!("{}5", quality).parse::<SpelledInterval>(),
+            Err(ParseIntervalError::InvalidQuality(quality))
+        );
+    }
+}
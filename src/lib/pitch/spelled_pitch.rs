@@ -0,0 +1,349 @@
+use super::accidental_constants::NATURAL;
+use super::note_constants::MIDI_NOTE_COUNT;
+use super::{
+    Accidental, Interval, Letter, Note, PitchClass, SpelledInterval, SpelledPitch,
+    SpelledPitchClass, CHROMATIC_COUNT, DIATONIC_COUNT,
+};
+
+/******************************************************************************
+* ERRORS
+******************************************************************************/
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParsePitchError {
+    Empty,
+    InvalidLetter(char),
+    MixedAccidentals,
+    InvalidOctave(String),
+    TrailingCharacters(String),
+    OutOfRange,
+}
+
+impl std::fmt::Display for ParsePitchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ParsePitchError::Empty => write!(f, "expected a pitch, found nothing"),
+            ParsePitchError::InvalidLetter(c) => {
+                write!(f, "expected a letter from A to G, found '{}'", c)
+            }
+            ParsePitchError::MixedAccidentals => write!(f, "cannot mix sharps and flats"),
+            ParsePitchError::InvalidOctave(s) => write!(f, "invalid octave \"{}\"", s),
+            ParsePitchError::TrailingCharacters(s) => {
+                write!(f, "unexpected trailing characters \"{}\"", s)
+            }
+            ParsePitchError::OutOfRange => write!(f, "pitch is outside of the MIDI note range"),
+        }
+    }
+}
+
+impl std::error::Error for ParsePitchError {}
+
+/******************************************************************************
+* IMPLS
+******************************************************************************/
+
+impl SpelledPitchClass {
+    pub fn new(letter: Letter, accidental: Accidental) -> SpelledPitchClass {
+        SpelledPitchClass { letter, accidental }
+    }
+
+    pub fn letter(self) -> Letter {
+        self.letter
+    }
+
+    pub fn accidental(self) -> Accidental {
+        self.accidental
+    }
+
+    pub fn pitch_class(self) -> PitchClass {
+        PitchClass(
+            (self.letter.semitones() + self.accidental.semitones())
+                .rem_euclid(CHROMATIC_COUNT as i8),
+        )
+    }
+
+    /// Spells `pitch_class` using `letter`, with whatever accidental that takes.
+    pub fn with_letter(pitch_class: PitchClass, letter: Letter) -> SpelledPitchClass {
+        SpelledPitchClass {
+            letter,
+            accidental: Accidental(signed_semitones(pitch_class.0 - letter.semitones())),
+        }
+    }
+
+    /// Spells black keys as sharps, e.g. `C#`.
+    pub fn sharp(pitch_class: PitchClass) -> SpelledPitchClass {
+        let letter = Letter::ALL
+            .iter()
+            .rev()
+            .find(|l| l.semitones() <= pitch_class.0)
+            .copied()
+            .unwrap_or(Letter::C);
+        SpelledPitchClass::with_letter(pitch_class, letter)
+    }
+
+    /// Spells black keys as flats, e.g. `Db`.
+    pub fn flat(pitch_class: PitchClass) -> SpelledPitchClass {
+        let letter = Letter::ALL
+            .iter()
+            .find(|l| l.semitones() >= pitch_class.0)
+            .copied()
+            .unwrap_or(Letter::C);
+        SpelledPitchClass::with_letter(pitch_class, letter)
+    }
+
+    /// Spells black keys the way they are usually named as keys: `Db`, `Eb`, `F#`, `Ab` and `Bb`.
+    pub fn common(pitch_class: PitchClass) -> SpelledPitchClass {
+        match pitch_class.0 {
+            6 => SpelledPitchClass::sharp(pitch_class),
+            _ => SpelledPitchClass::flat(pitch_class),
+        }
+    }
+
+    /// Spells each degree of a heptatonic `scale` on its own letter, starting from `self`.
+    pub fn spell_scale(self, scale: &[Interval]) -> Vec<SpelledPitchClass> {
+        assert_eq!(scale.len(), DIATONIC_COUNT);
+        let tonic = self.pitch_class().0;
+        scale
+            .iter()
+            .enumerate()
+            .map(|(i, interval)| {
+                SpelledPitchClass::with_letter(
+                    PitchClass((tonic + interval.0).rem_euclid(CHROMATIC_COUNT as i8)),
+                    self.letter.offset(i as i8),
+                )
+            })
+            .collect()
+    }
+
+    /// Spells `pitch_class` as it would be written in the key of `self` with the heptatonic `scale`.
+    ///
+    /// Scale tones get their diatonic spelling. Chromatic tones deviate as little as possible from
+    /// the key signature, then prefer fewer accidentals, then follow the direction of the key signature.
+    pub fn spell_in_scale(self, scale: &[Interval], pitch_class: PitchClass) -> SpelledPitchClass {
+        let degrees = self.spell_scale(scale);
+        let signature: i8 = degrees.iter().map(|d| d.accidental.0).sum();
+
+        degrees
+            .iter()
+            .map(|degree| {
+                let candidate = SpelledPitchClass::with_letter(pitch_class, degree.letter);
+                let deviation = (candidate.accidental.0 - degree.accidental.0).abs();
+                let against_signature = if signature < 0 {
+                    candidate.accidental.0 > degree.accidental.0
+                } else {
+                    candidate.accidental.0 < degree.accidental.0
+                };
+                (
+                    (deviation, candidate.accidental.0.abs(), against_signature),
+                    candidate,
+                )
+            })
+            .min_by_key(|&(cost, _)| cost)
+            .map(|(_, candidate)| candidate)
+            .unwrap()
+    }
+
+    pub fn with_octave(self, octave: i8) -> SpelledPitch {
+        SpelledPitch::new(self.letter, self.accidental, octave)
+    }
+
+    /// Moves by `interval`, keeping its spelling, e.g. `E` up a diminished fifth is `Bb`.
+    pub fn transpose(self, interval: SpelledInterval) -> SpelledPitchClass {
+        self.with_octave(4).transpose(interval).class()
+    }
+}
+
+impl SpelledPitch {
+    pub fn new(letter: Letter, accidental: Accidental, octave: i8) -> SpelledPitch {
+        SpelledPitch {
+            letter,
+            accidental,
+            octave,
+        }
+    }
+
+    /// Spells `note` as `class`, or returns `None` if they are not enharmonically equivalent.
+    pub fn from_note(note: Note, class: SpelledPitchClass) -> Option<SpelledPitch> {
+        let natural = note.0 as i16 - class.accidental.semitones() as i16;
+        let letter_offset = natural - class.letter.semitones() as i16;
+        if letter_offset.rem_euclid(CHROMATIC_COUNT as i16) != 0 {
+            return None;
+        }
+        Some(class.with_octave((letter_offset.div_euclid(CHROMATIC_COUNT as i16) - 1) as i8))
+    }
+
+    /// Spells black keys as sharps, e.g. `C#4`.
+    pub fn sharp(note: Note) -> SpelledPitch {
+        SpelledPitch::from_note(note, SpelledPitchClass::sharp(note.pitch_class())).unwrap()
+    }
+
+    /// Spells black keys as flats, e.g. `Db4`.
+    pub fn flat(note: Note) -> SpelledPitch {
+        SpelledPitch::from_note(note, SpelledPitchClass::flat(note.pitch_class())).unwrap()
+    }
+
+    /// Spells `note` as it would be written in the key of `tonic` with the heptatonic `scale`.
+    pub fn in_scale(note: Note, tonic: SpelledPitchClass, scale: &[Interval]) -> SpelledPitch {
+        let class = tonic.spell_in_scale(scale, note.pitch_class());
+        SpelledPitch::from_note(note, class).unwrap()
+    }
+
+    pub fn letter(self) -> Letter {
+        self.letter
+    }
+
+    pub fn accidental(self) -> Accidental {
+        self.accidental
+    }
+
+    pub fn octave(self) -> i8 {
+        self.octave
+    }
+
+    pub fn class(self) -> SpelledPitchClass {
+        SpelledPitchClass::new(self.letter, self.accidental)
+    }
+
+    /// Steps on the staff above `C-1`, ignoring accidentals.
+    pub fn staff_position(self) -> i16 {
+        (self.octave as i16 + 1) * DIATONIC_COUNT as i16 + self.letter.index() as i16
+    }
+
+    /// Semitones above `C-1`, which may lie outside of the MIDI note range.
+    pub fn midi_number(self) -> i16 {
+        (self.octave as i16 + 1) * CHROMATIC_COUNT as i16
+            + self.letter.semitones() as i16
+            + self.accidental.semitones() as i16
+    }
+
+    /// Returns `None` outside of the MIDI note range.
+    pub fn note(self) -> Option<Note> {
+        let x = self.midi_number();
+        if 0 <= x && x < MIDI_NOTE_COUNT as i16 {
+            Some(Note(x as i8))
+        } else {
+            None
+        }
+    }
+
+    /// Moves by `interval`, keeping its spelling, e.g. `E4` up a diminished fifth is `Bb4`.
+    pub fn transpose(self, interval: SpelledInterval) -> SpelledPitch {
+        let position = self.staff_position() + interval.steps() as i16;
+        let letter = Letter::from_index(position.rem_euclid(DIATONIC_COUNT as i16) as i8);
+        let octave = position.div_euclid(DIATONIC_COUNT as i16) - 1;
+        let natural = (octave + 1) * CHROMATIC_COUNT as i16 + letter.semitones() as i16;
+        let target = self.midi_number() + interval.interval().0 as i16;
+        SpelledPitch::new(letter, Accidental((target - natural) as i8), octave as i8)
+    }
+}
+
+impl std::ops::Add<SpelledInterval> for SpelledPitch {
+    type Output = Self;
+
+    fn add(self, other: SpelledInterval) -> Self {
+        self.transpose(other)
+    }
+}
+
+impl std::ops::Sub<SpelledInterval> for SpelledPitch {
+    type Output = Self;
+
+    fn sub(self, other: SpelledInterval) -> Self {
+        self.transpose(-other)
+    }
+}
+
+impl std::ops::Sub<SpelledPitch> for SpelledPitch {
+    type Output = SpelledInterval;
+
+    fn sub(self, other: SpelledPitch) -> SpelledInterval {
+        SpelledInterval::between(other, self)
+    }
+}
+
+impl std::ops::Add<SpelledInterval> for SpelledPitchClass {
+    type Output = Self;
+
+    fn add(self, other: SpelledInterval) -> Self {
+        self.transpose(other)
+    }
+}
+
+impl From<SpelledPitch> for SpelledPitchClass {
+    fn from(x: SpelledPitch) -> SpelledPitchClass {
+        x.class()
+    }
+}
+
+impl std::fmt::Display for SpelledPitchClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}{}", self.letter, self.accidental)
+    }
+}
+
+impl std::fmt::Display for SpelledPitch {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}{}{}", self.letter, self.accidental, self.octave)
+    }
+}
+
+impl std::str::FromStr for SpelledPitchClass {
+    type Err = ParsePitchError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (class, rest) = parse_spelled_pitch_class(s)?;
+        if !rest.is_empty() {
+            return Err(ParsePitchError::TrailingCharacters(rest.to_string()));
+        }
+        Ok(class)
+    }
+}
+
+impl std::str::FromStr for SpelledPitch {
+    type Err = ParsePitchError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (class, rest) = parse_spelled_pitch_class(s)?;
+        let octave = rest
+            .parse::<i8>()
+            .map_err(|_| ParsePitchError::InvalidOctave(rest.to_string()))?;
+        Ok(class.with_octave(octave))
+    }
+}
+
+/// Parses a letter followed by any number of either sharps or flats, returning the unparsed rest.
+pub fn parse_spelled_pitch_class(s: &str) -> Result<(SpelledPitchClass, &str), ParsePitchError> {
+    let mut chars = s.char_indices().peekable();
+    let letter = match chars.next() {
+        None => return Err(ParsePitchError::Empty),
+        Some((_, c)) => Letter::from_char(c).ok_or(ParsePitchError::InvalidLetter(c))?,
+    };
+
+    let mut accidental = NATURAL;
+    let mut rest = &s[letter.to_char().len_utf8()..];
+    while let Some(&(i, c)) = chars.peek() {
+        let step = match c {
+            '#' | '♯' => 1,
+            'b' | '♭' => -1,
+            _ => break,
+        };
+        if accidental.0 * step < 0 {
+            return Err(ParsePitchError::MixedAccidentals);
+        }
+        accidental.0 += step;
+        chars.next();
+        rest = &s[i + c.len_utf8()..];
+    }
+
+    Ok((SpelledPitchClass::new(letter, accidental), rest))
+}
+
+/// Maps semitones onto `-5..=6`.
+fn signed_semitones(x: i8) -> i8 {
+    let x = x.rem_euclid(CHROMATIC_COUNT as i8);
+    if x > CHROMATIC_COUNT as i8 / 2 {
+        x - CHROMATIC_COUNT as i8
+    } else {
+        x
+    }
+}
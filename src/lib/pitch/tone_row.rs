@@ -0,0 +1,172 @@
+use rand::{seq::SliceRandom, Rng};
+
+use super::{Interval, PitchClass, PitchClassSet, RowForm, ToneRow, CHROMATIC_COUNT};
+
+/******************************************************************************
+* ERRORS
+******************************************************************************/
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ToneRowError {
+    WrongLength(usize),
+    Repeated(PitchClass),
+}
+
+impl std::fmt::Display for ToneRowError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ToneRowError::WrongLength(n) => {
+                write!(f, "a tone row needs 12 pitch classes, found {}", n)
+            }
+            ToneRowError::Repeated(pc) => write!(f, "pitch class {} occurs more than once", pc),
+        }
+    }
+}
+
+impl std::error::Error for ToneRowError {}
+
+/******************************************************************************
+* IMPLS
+******************************************************************************/
+
+impl RowForm {
+    pub const ALL: [RowForm; 4] = [
+        RowForm::Prime,
+        RowForm::Retrograde,
+        RowForm::Inversion,
+        RowForm::RetrogradeInversion,
+    ];
+
+    /// `P`, `R`, `I` or `RI`, as used in labels like `RI3`.
+    pub fn abbreviation(self) -> &'static str {
+        match self {
+            RowForm::Prime => "P",
+            RowForm::Retrograde => "R",
+            RowForm::Inversion => "I",
+            RowForm::RetrogradeInversion => "RI",
+        }
+    }
+}
+
+impl ToneRow {
+    pub fn new(pitch_classes: &[PitchClass]) -> Result<ToneRow, ToneRowError> {
+        if pitch_classes.len() != CHROMATIC_COUNT {
+            return Err(ToneRowError::WrongLength(pitch_classes.len()));
+        }
+        let mut seen = PitchClassSet::new();
+        let mut row = [PitchClass::default(); CHROMATIC_COUNT];
+        for (i, &pc) in pitch_classes.iter().enumerate() {
+            if seen.contains(pc) {
+                return Err(ToneRowError::Repeated(pc));
+            }
+            seen.insert(pc);
+            row[i] = pc;
+        }
+        Ok(ToneRow(row))
+    }
+
+    pub fn random<R: Rng>(rng: &mut R) -> ToneRow {
+        let mut row = [PitchClass::default(); CHROMATIC_COUNT];
+        for (i, pc) in row.iter_mut().enumerate() {
+            *pc = PitchClass(i as i8);
+        }
+        row.shuffle(rng);
+        ToneRow(row)
+    }
+
+    pub fn pitch_classes(&self) -> &[PitchClass; CHROMATIC_COUNT] {
+        &self.0
+    }
+
+    /// The row in `form`, labelled by absolute pitch class.
+    ///
+    /// `P` and `I` forms start on `transposition` while `R` and `RI` forms end on it, so e.g. `R5`
+    /// is `P5` backwards.
+    pub fn form(&self, form: RowForm, transposition: PitchClass) -> [PitchClass; CHROMATIC_COUNT] {
+        let first = self.0[0];
+        let mut result = self.0;
+        for pc in result.iter_mut() {
+            *pc = match form {
+                RowForm::Prime | RowForm::Retrograde => transposition + (*pc - first),
+                RowForm::Inversion | RowForm::RetrogradeInversion => transposition - (*pc - first),
+            };
+        }
+        if let RowForm::Retrograde | RowForm::RetrogradeInversion = form {
+            result.reverse();
+        }
+        result
+    }
+
+    pub fn prime(&self, transposition: PitchClass) -> [PitchClass; CHROMATIC_COUNT] {
+        self.form(RowForm::Prime, transposition)
+    }
+
+    pub fn retrograde(&self, transposition: PitchClass) -> [PitchClass; CHROMATIC_COUNT] {
+        self.form(RowForm::Retrograde, transposition)
+    }
+
+    pub fn inversion(&self, transposition: PitchClass) -> [PitchClass; CHROMATIC_COUNT] {
+        self.form(RowForm::Inversion, transposition)
+    }
+
+    pub fn retrograde_inversion(&self, transposition: PitchClass) -> [PitchClass; CHROMATIC_COUNT] {
+        self.form(RowForm::RetrogradeInversion, transposition)
+    }
+
+    /// https://en.wikipedia.org/wiki/Twelve-tone_technique#Tone_row
+    ///
+    /// Row `i` reads as a `P` form left to right, column `j` as an `I` form top to bottom.
+    pub fn matrix(&self) -> [[PitchClass; CHROMATIC_COUNT]; CHROMATIC_COUNT] {
+        let first = self.0[0];
+        let mut matrix = [[PitchClass::default(); CHROMATIC_COUNT]; CHROMATIC_COUNT];
+        for (row, &pc) in matrix.iter_mut().zip(self.0.iter()) {
+            *row = self.prime(first - (pc - first));
+        }
+        matrix
+    }
+
+    /// Renders `matrix` in integer notation, with `P`/`R` labels on the sides and `I`/`RI` labels
+    /// above and below.
+    pub fn render_matrix(&self) -> String {
+        let matrix = self.matrix();
+        let label = |form: RowForm, pc: PitchClass| format!("{}{}", form.abbreviation(), pc.0);
+        let mut out = String::new();
+
+        out.push_str("     ");
+        for &pc in matrix[0].iter() {
+            out.push_str(&format!("{:>5}", label(RowForm::Inversion, pc)));
+        }
+        out.push('\n');
+        for row in matrix.iter() {
+            out.push_str(&format!("{:<5}", label(RowForm::Prime, row[0])));
+            for &pc in row.iter() {
+                out.push_str(&format!("{:>5}", pc.integer_notation()));
+            }
+            out.push_str(&format!("  {}\n", label(RowForm::Retrograde, row[0])));
+        }
+        out.push_str("     ");
+        for &pc in matrix[0].iter() {
+            out.push_str(&format!("{:>5}", label(RowForm::RetrogradeInversion, pc)));
+        }
+        out.push('\n');
+        out
+    }
+
+    /// Each of the eleven intervals between adjacent pitch classes, ascending within an octave.
+    pub fn intervals(&self) -> Vec<Interval> {
+        self.0.windows(2).map(|w| w[1] - w[0]).collect()
+    }
+}
+
+/// Prints the row in integer notation, e.g. `0 1 4 ...`.
+impl std::fmt::Display for ToneRow {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for (i, pc) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{}", pc.integer_notation())?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,26 @@
+use super::{BarPosition, BeatDuration};
+
+impl BarPosition {
+    pub fn new(bar: usize, beat: u64, tick: BeatDuration) -> BarPosition {
+        BarPosition { bar, beat, tick }
+    }
+
+    pub fn bar(self) -> usize {
+        self.bar
+    }
+
+    pub fn beat(self) -> u64 {
+        self.beat
+    }
+
+    pub fn tick(self) -> BeatDuration {
+        self.tick
+    }
+}
+
+/// Prints e.g. `3:2:60` for bar 3, beat 2, 60 ticks in.
+impl std::fmt::Display for BarPosition {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}:{}:{}", self.bar, self.beat, self.tick.0)
+    }
+}
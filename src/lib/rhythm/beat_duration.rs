@@ -74,6 +74,14 @@ impl std::ops::Add<BeatDuration> for BeatDuration {
     }
 }
 
+impl std::ops::Sub<BeatDuration> for BeatDuration {
+    type Output = Self;
+
+    fn sub(self, other: BeatDuration) -> Self {
+        BeatDuration(self.0 - other.0)
+    }
+}
+
 impl std::ops::Mul<u64> for BeatDuration {
     type Output = Self;
 
@@ -90,6 +98,15 @@ impl std::ops::Div<u64> for BeatDuration {
     }
 }
 
+/// How many whole times `other` fits.
+impl std::ops::Div<BeatDuration> for BeatDuration {
+    type Output = u64;
+
+    fn div(self, other: BeatDuration) -> u64 {
+        self.0 / other.0
+    }
+}
+
 impl std::ops::Rem<u64> for BeatDuration {
     type Output = u64;
 
@@ -26,6 +26,23 @@ impl std::ops::Add<BeatDuration> for BeatTime {
     }
 }
 
+impl std::ops::Sub<BeatTime> for BeatTime {
+    type Output = BeatDuration;
+
+    fn sub(self, other: BeatTime) -> BeatDuration {
+        BeatDuration(self.0 - other.0)
+    }
+}
+
+/// How far into a bar, or any other span, the time lies.
+impl std::ops::Rem<BeatDuration> for BeatTime {
+    type Output = BeatDuration;
+
+    fn rem(self, other: BeatDuration) -> BeatDuration {
+        BeatDuration(self.0 % other.0)
+    }
+}
+
 impl std::ops::Mul<u64> for BeatTime {
     type Output = Self;
 
@@ -0,0 +1,272 @@
+use super::{BeatDuration, BeatTime, DrumGrid, DrumHit, Rhythm, RhythmEvent};
+
+/******************************************************************************
+* ERRORS
+******************************************************************************/
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum DrumGridError {
+    InvalidSymbol(char),
+    ZeroStep,
+    OffGrid(BeatDuration),
+    HitCount { notes: usize, hits: usize },
+}
+
+impl std::fmt::Display for DrumGridError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            DrumGridError::InvalidSymbol(c) => write!(
+                f,
+                "invalid drum grid symbol '{}', expected one of X, x, o, - or .",
+                c
+            ),
+            DrumGridError::ZeroStep => write!(f, "a drum grid step must be longer than zero"),
+            DrumGridError::OffGrid(d) => {
+                write!(f, "{} ticks is not a whole number of drum grid steps", d.0)
+            }
+            DrumGridError::HitCount { notes, hits } => write!(
+                f,
+                "a rhythm of {} notes cannot be struck with {} hits",
+                notes, hits
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DrumGridError {}
+
+/******************************************************************************
+* CONSTANTS
+******************************************************************************/
+
+/// The symbol of a step that strikes nothing, letting the note before ring on.
+pub const DRUM_GRID_HOLD: char = '-';
+
+/// The symbol of a step of rest.
+pub const DRUM_GRID_REST: char = '.';
+
+/******************************************************************************
+* IMPLS
+******************************************************************************/
+
+impl DrumHit {
+    pub const ALL: [DrumHit; 3] = [DrumHit::Accent, DrumHit::Normal, DrumHit::Ghost];
+
+    /// How loud the hit is, from 0 to 1.
+    pub fn velocity(self) -> f64 {
+        match self {
+            DrumHit::Accent => 1.0,
+            DrumHit::Normal => 0.7,
+            DrumHit::Ghost => 0.35,
+        }
+    }
+
+    pub fn symbol(self) -> char {
+        match self {
+            DrumHit::Accent => 'X',
+            DrumHit::Normal => 'x',
+            DrumHit::Ghost => 'o',
+        }
+    }
+
+    pub fn from_symbol(symbol: char) -> Option<DrumHit> {
+        DrumHit::ALL
+            .iter()
+            .copied()
+            .find(|hit| hit.symbol() == symbol)
+    }
+}
+
+impl DrumGrid {
+    /// `rhythm` struck with `hits`, one per note, on a grid of `step`. Ties are merged into the
+    /// notes they carry on, and rests in a row into one.
+    pub fn new(
+        rhythm: Rhythm,
+        hits: Vec<DrumHit>,
+        step: BeatDuration,
+    ) -> Result<DrumGrid, DrumGridError> {
+        if step == BeatDuration::default() {
+            return Err(DrumGridError::ZeroStep);
+        }
+        if let Some(&d) = rhythm.durations().iter().find(|&&d| d % step.0 != 0) {
+            return Err(DrumGridError::OffGrid(d));
+        }
+        let notes = rhythm.notes().len();
+        if notes != hits.len() {
+            return Err(DrumGridError::HitCount {
+                notes,
+                hits: hits.len(),
+            });
+        }
+        let mut merged = Rhythm::default();
+        let mut end = BeatTime::zero();
+        for (onset, duration) in rhythm.notes() {
+            if onset > end {
+                merged.push(RhythmEvent::Rest, onset - end);
+            }
+            merged.push(RhythmEvent::Onset, duration);
+            end = onset + duration;
+        }
+        if BeatTime::zero() + rhythm.length() > end {
+            merged.push(RhythmEvent::Rest, BeatTime::zero() + rhythm.length() - end);
+        }
+        Ok(DrumGrid {
+            step,
+            rhythm: merged,
+            hits,
+        })
+    }
+
+    /// Reads a grid written one symbol per step, ignoring whitespace and `|` so bars can be set
+    /// apart, e.g. `X--x -o-- | x-x- o...`. Holds before the first hit or after a rest rest too.
+    pub fn parse(s: &str, step: BeatDuration) -> Result<DrumGrid, DrumGridError> {
+        if step == BeatDuration::default() {
+            return Err(DrumGridError::ZeroStep);
+        }
+        let mut rhythm = Rhythm::default();
+        let mut hits = Vec::new();
+        for symbol in s.chars().filter(|&c| !c.is_whitespace() && c != '|') {
+            let last = rhythm.events.last().copied();
+            if symbol == DRUM_GRID_HOLD && last.is_some()
+                || symbol == DRUM_GRID_REST && last == Some(RhythmEvent::Rest)
+            {
+                let duration = rhythm.durations.last_mut().unwrap();
+                *duration = *duration + step;
+                continue;
+            }
+            if symbol == DRUM_GRID_HOLD || symbol == DRUM_GRID_REST {
+                rhythm.push(RhythmEvent::Rest, step);
+                continue;
+            }
+            let hit = DrumHit::from_symbol(symbol).ok_or(DrumGridError::InvalidSymbol(symbol))?;
+            rhythm.push(RhythmEvent::Onset, step);
+            hits.push(hit);
+        }
+        Ok(DrumGrid { step, rhythm, hits })
+    }
+
+    pub fn step(&self) -> BeatDuration {
+        self.step
+    }
+
+    pub fn rhythm(&self) -> &Rhythm {
+        &self.rhythm
+    }
+
+    pub fn hits(&self) -> &[DrumHit] {
+        &self.hits
+    }
+
+    /// How loud each note of the rhythm is struck.
+    pub fn velocities(&self) -> Vec<f64> {
+        self.hits.iter().map(|hit| hit.velocity()).collect()
+    }
+
+    /// How many steps the grid spans.
+    pub fn steps(&self) -> u64 {
+        self.rhythm.length() / self.step
+    }
+}
+
+/// Prints one symbol per step, so that parsing the result at the same step gives the grid back.
+impl std::fmt::Display for DrumGrid {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let mut hits = self.hits.iter();
+        for (&event, &duration) in self.rhythm.events.iter().zip(self.rhythm.durations.iter()) {
+            let steps = duration / self.step;
+            let (first, rest) = match event {
+                RhythmEvent::Rest => (DRUM_GRID_REST, DRUM_GRID_REST),
+                _ => (hits.next().unwrap().symbol(), DRUM_GRID_HOLD),
+            };
+            write!(f, "{}", first)?;
+            for _ in 1..steps {
+                write!(f, "{}", rest)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/******************************************************************************
+* TESTS
+******************************************************************************/
+
+#[cfg(test)]
+mod tests {
+    use super::super::{RhythmEmbellishment, EN, HN, QN, SN};
+    use super::*;
+
+    #[test]
+    fn grids_round_trip() {
+        for &s in ["X--x-o..", "..x-..x", "X...x-o-o...", "x", "."].iter() {
+            let grid = DrumGrid::parse(s, SN).unwrap();
+            assert_eq!(grid.to_string(), s);
+            assert_eq!(DrumGrid::parse(&grid.to_string(), grid.step()), Ok(grid));
+        }
+        let grid = DrumGrid::parse("X--x | -o-- ", EN).unwrap();
+        assert_eq!(grid.to_string(), "X--x-o--");
+        assert_eq!(grid.steps(), 8);
+        assert_eq!(grid.velocities(), vec![1.0, 0.7, 0.35]);
+    }
+
+    #[test]
+    fn rhythms_round_trip() {
+        let rhythm = Rhythm::from_durations(&[QN, QN]).concat(&Rhythm::rest(HN));
+        let grid = DrumGrid::new(rhythm, vec![DrumHit::Accent, DrumHit::Normal], SN).unwrap();
+        assert_eq!(grid.to_string(), "X---x---........");
+        assert_eq!(DrumGrid::parse(&grid.to_string(), grid.step()), Ok(grid));
+
+        let mut tied = Rhythm::new(QN);
+        tied.push(RhythmEvent::Tie, QN);
+        tied.push(RhythmEvent::Rest, QN);
+        tied.push(RhythmEvent::Rest, QN);
+        let grid = DrumGrid::new(tied, vec![DrumHit::Ghost], QN).unwrap();
+        assert_eq!(grid.to_string(), "o-..");
+        assert_eq!(DrumGrid::parse(&grid.to_string(), grid.step()), Ok(grid));
+    }
+
+    #[test]
+    fn invalid_grids() {
+        assert_eq!(
+            DrumGrid::parse("x-q", SN),
+            Err(DrumGridError::InvalidSymbol('q'))
+        );
+        assert_eq!(
+            DrumGrid::parse("x", BeatDuration::default()),
+            Err(DrumGridError::ZeroStep)
+        );
+        let rhythm = Rhythm::from_durations(&[QN, EN]);
+        assert_eq!(
+            DrumGrid::new(rhythm.clone(), vec![DrumHit::Normal; 2], QN),
+            Err(DrumGridError::OffGrid(EN))
+        );
+        assert_eq!(
+            DrumGrid::new(rhythm, vec![DrumHit::Normal], EN),
+            Err(DrumGridError::HitCount { notes: 2, hits: 1 })
+        );
+    }
+
+    #[test]
+    fn embellished_grids() {
+        let embellish = |s: &str, embellishment: RhythmEmbellishment| {
+            let grid = DrumGrid::parse(s, SN).unwrap();
+            let rhythm = grid.rhythm().embellished(0, SN, embellishment)?;
+            let hits = vec![DrumHit::Normal; rhythm.notes().len()];
+            Some(DrumGrid::new(rhythm, hits, SN).unwrap().to_string())
+        };
+        assert_eq!(
+            embellish("x---", RhythmEmbellishment::Roll),
+            Some("xxx-".to_string())
+        );
+        assert_eq!(
+            embellish("x---", RhythmEmbellishment::Anticipation),
+            Some("x--x".to_string())
+        );
+        assert_eq!(
+            embellish("x-x-", RhythmEmbellishment::Roll),
+            Some("x-x-".to_string())
+        );
+        assert_eq!(embellish("xx--", RhythmEmbellishment::Roll), None);
+        assert_eq!(embellish("..x-", RhythmEmbellishment::Roll), None);
+    }
+}
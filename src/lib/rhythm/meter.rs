@@ -0,0 +1,205 @@
+use super::{BeatDuration, BeatTime, Meter, Subdivision};
+
+impl Meter {
+    /// A single level holding the whole bar.
+    pub fn new(bar: BeatDuration) -> Meter {
+        assert!(bar > BeatDuration::default());
+        Meter {
+            levels: vec![vec![bar]],
+        }
+    }
+
+    /// `beats` beats of `beat` each, split in halves, e.g. `simple(4, QN)` for 4/4 with halves,
+    /// quarters and eighths. Even numbers of beats above two are paired up first. Beats of an odd
+    /// number of ticks are left whole. Panics on no beats or empty beats.
+    pub fn simple(beats: u64, beat: BeatDuration) -> Meter {
+        assert!(beats > 0 && beat > BeatDuration::default());
+        let meter = Meter::grouped(Meter::new(beat * beats), beats, beat);
+        if beat.0.is_multiple_of(2) {
+            meter.subdivided_into(beat / 2).unwrap()
+        } else {
+            meter
+        }
+    }
+
+    /// `beats` dotted beats of three `unit`s each, e.g. `compound(2, EN)` for 6/8. Panics on no
+    /// beats or empty units.
+    pub fn compound(beats: u64, unit: BeatDuration) -> Meter {
+        assert!(beats > 0 && unit > BeatDuration::default());
+        Meter::grouped(Meter::new(unit * 3 * beats), beats, unit * 3)
+            .subdivided_into(unit)
+            .unwrap()
+    }
+
+    /// Groups of `unit`s, e.g. `additive(&[2, 2, 3], EN)` for 7/8 as 2+2+3. Panics on no groups,
+    /// empty groups or empty units.
+    pub fn additive(groups: &[u64], unit: BeatDuration) -> Meter {
+        assert!(!groups.is_empty() && groups.iter().all(|&group| group > 0));
+        assert!(unit > BeatDuration::default());
+        let total = groups.iter().sum();
+        Meter::new(unit * total)
+            .subdivided(&Subdivision::new(&groups.to_vec()))
+            .unwrap()
+            .subdivided_into(unit)
+            .unwrap()
+    }
+
+    /// The bar of `meter` split into `beats` of `beat`, which always divides, paired up first where
+    /// there are an even number of them above two.
+    fn grouped(meter: Meter, beats: u64, beat: BeatDuration) -> Meter {
+        let meter = if beats > 2 && beats.is_multiple_of(2) {
+            meter.subdivided(&Subdivision::new(&vec![1, 1])).unwrap()
+        } else {
+            meter
+        };
+        meter.subdivided_into(beat).unwrap()
+    }
+
+    /// A new level splitting every part of the finest level by `sub`, or None where a part does not
+    /// divide evenly.
+    pub fn subdivided(&self, sub: &Subdivision) -> Option<Meter> {
+        let mut level = Vec::new();
+        for &part in self.finest() {
+            level.extend(part.subdivided(sub.clone())?);
+        }
+        let mut meter = self.clone();
+        meter.levels.push(level);
+        Some(meter)
+    }
+
+    /// A new level splitting every part of the finest level into parts of `unit`, or None where a
+    /// part is no multiple of it. Parts already of `unit` are left as they are.
+    pub fn subdivided_into(&self, unit: BeatDuration) -> Option<Meter> {
+        if unit == BeatDuration::default() || self.finest().iter().all(|&part| part == unit) {
+            return Some(self.clone());
+        }
+        let mut level = Vec::new();
+        for &part in self.finest() {
+            if part % unit.0 != 0 {
+                return None;
+            }
+            let count = part.0 / unit.0;
+            level.extend((0..count).map(|_| unit));
+        }
+        let mut meter = self.clone();
+        meter.levels.push(level);
+        Some(meter)
+    }
+
+    pub fn bar(&self) -> BeatDuration {
+        self.levels[0][0]
+    }
+
+    /// The parts of each level, from the whole bar down.
+    pub fn levels(&self) -> &[Vec<BeatDuration>] {
+        &self.levels
+    }
+
+    fn finest(&self) -> &[BeatDuration] {
+        self.levels.last().unwrap()
+    }
+
+    /// How many levels start a part at `time`, counting from the start of its bar: as many as there
+    /// are levels on the downbeat, and 0 off the finest level.
+    pub fn weight(&self, time: BeatTime) -> usize {
+        let position = time % self.bar();
+        self.levels
+            .iter()
+            .filter(|level| {
+                let mut onset = BeatDuration::default();
+                for &part in level.iter() {
+                    if onset >= position {
+                        break;
+                    }
+                    onset = onset + part;
+                }
+                onset == position
+            })
+            .count()
+    }
+
+    /// The weight of `time` from 0 off the grid to 1 on the downbeat.
+    pub fn emphasis(&self, time: BeatTime) -> f64 {
+        self.weight(time) as f64 / self.levels.len() as f64
+    }
+
+    /// Each position of the finest level within a bar, with its weight.
+    pub fn grid(&self) -> Vec<(BeatDuration, usize)> {
+        let mut onset = BeatDuration::default();
+        self.finest()
+            .iter()
+            .map(|&part| {
+                let position = onset;
+                onset = onset + part;
+                (position, self.weight(BeatTime::zero() + position))
+            })
+            .collect()
+    }
+}
+
+/// Prints the parts of each level in units of the finest level, e.g. `7 | 2+2+3 | 1+1+1+1+1+1+1`.
+impl std::fmt::Display for Meter {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let unit = self.finest().iter().min().unwrap().0;
+        for (i, level) in self.levels.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" | ")?;
+            }
+            let parts: Vec<String> = level
+                .iter()
+                .map(|&part| (part.0 / unit).to_string())
+                .collect();
+            f.write_str(&parts.join("+"))?;
+        }
+        Ok(())
+    }
+}
+
+/******************************************************************************
+* TESTS
+******************************************************************************/
+
+#[cfg(test)]
+mod tests {
+    use super::super::{EN, QN, SN};
+    use super::*;
+
+    fn weights(meter: &Meter) -> Vec<usize> {
+        meter.grid().iter().map(|&(_, weight)| weight).collect()
+    }
+
+    #[test]
+    fn weights_of_common_meters() {
+        let common = Meter::simple(4, QN);
+        assert_eq!(weights(&common), [4, 1, 2, 1, 3, 1, 2, 1]);
+        let six_eight = Meter::compound(2, EN);
+        assert_eq!(weights(&six_eight), [3, 1, 1, 2, 1, 1]);
+        let seven_eight = Meter::additive(&[2, 2, 3], EN);
+        assert_eq!(weights(&seven_eight), [3, 1, 2, 1, 2, 1, 1]);
+        assert_eq!(weights(&Meter::simple(3, QN)), [3, 1, 2, 1, 2, 1]);
+    }
+
+    #[test]
+    fn weights_off_the_grid_and_in_later_bars() {
+        let meter = Meter::simple(4, QN);
+        assert_eq!(meter.weight(BeatTime::zero() + SN), 0);
+        assert_eq!(meter.weight(BeatTime::zero() + meter.bar()), 4);
+        assert_eq!(meter.weight(BeatTime::zero() + meter.bar() + QN * 2), 3);
+        assert_eq!(meter.emphasis(BeatTime::zero()), 1.);
+        assert_eq!(meter.emphasis(BeatTime::zero() + QN), 0.5);
+    }
+
+    #[test]
+    fn levels_in_units_of_the_finest() {
+        assert_eq!(
+            Meter::simple(4, QN).to_string(),
+            "8 | 4+4 | 2+2+2+2 | 1+1+1+1+1+1+1+1"
+        );
+        assert_eq!(Meter::compound(2, EN).to_string(), "6 | 3+3 | 1+1+1+1+1+1");
+        assert_eq!(
+            Meter::additive(&[2, 2, 3], EN).to_string(),
+            "7 | 2+2+3 | 1+1+1+1+1+1+1"
+        );
+        assert_eq!(Meter::new(QN * 3).to_string(), "1");
+    }
+}
@@ -1,24 +1,140 @@
+mod bar_position;
 mod beat_duration;
 mod beat_time;
+mod drum_grid;
+mod meter;
 mod rhythm;
 mod subdivision;
+mod tempo_map;
+mod time_signature;
+mod time_signature_map;
 
 pub use beat_duration::*;
 pub use beat_time::*;
+pub use drum_grid::*;
 pub use rhythm::*;
 pub use subdivision::*;
+pub use time_signature::*;
 
-#[derive(Clone, Copy, Default, Debug, PartialEq, PartialOrd)]
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct BeatDuration(u64);
 
-#[derive(Clone, Copy, Default, Debug, PartialEq, PartialOrd)]
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct BeatTime(u64);
 
+/// What happens over each duration of a rhythm.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RhythmEvent {
+    /// A note starts.
+    Onset,
+    Rest,
+    /// The note before carries on.
+    Tie,
+}
+
+/// https://en.wikipedia.org/wiki/Embellishment
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RhythmEmbellishment {
+    /// Repeats a note every step up to its last one, e.g. `x---` becoming `xxx-`.
+    Roll,
+    /// Strikes again on the last step of a note, leading into the next, e.g. `x---` becoming
+    /// `x--x`.
+    Anticipation,
+}
+
+/// https://en.wikipedia.org/wiki/Rhythm
+///
+/// Durations one after the other from time zero, each starting a note, resting or tied to the note
+/// before.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
 pub struct Rhythm {
     durations: Vec<BeatDuration>,
+    events: Vec<RhythmEvent>,
+}
+
+/// https://en.wikipedia.org/wiki/Metre_(music)
+///
+/// A bar as levels of ever finer parts, each level subdividing the parts of the one above, e.g. 7/8
+/// as a bar of 2+2+3 eighths, then single eighths. The more levels a position starts a part on, the
+/// stronger it is.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Meter {
+    levels: Vec<Vec<BeatDuration>>,
 }
 
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Subdivision {
     total: u64,
     subs: Vec<u64>,
 }
+
+/// https://en.wikipedia.org/wiki/Time_signature
+///
+/// `numerator` beats of a `1/denominator` note each, the denominator being a power of two.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TimeSignature {
+    numerator: u64,
+    denominator: u64,
+}
+
+/// Where a time falls as a bar, a beat of the bar's time signature and the ticks since that beat,
+/// all counted from 0.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BarPosition {
+    bar: usize,
+    beat: u64,
+    tick: BeatDuration,
+}
+
+/// Time signatures from the bar they take effect in, optionally after a pickup bar.
+///
+/// https://en.wikipedia.org/wiki/Anacrusis
+///
+/// A pickup is bar 0, with the first full bar being bar 1. Positions within it are counted as if
+/// it were the end of a full bar, so a quarter note pickup in 4/4 falls on beat 3.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TimeSignatureMap {
+    signatures: Vec<(usize, TimeSignature)>,
+    pickup: Option<BeatDuration>,
+}
+
+/// https://en.wikipedia.org/wiki/Tempo#Variation_through_a_piece
+///
+/// How the tempo moves toward a tempo change, from the tempo at the change before it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TempoRamp {
+    /// Holds the old tempo until the change, then jumps.
+    Instant,
+    /// Changes by the same number of beats per minute every beat.
+    Linear,
+    /// Changes by the same ratio every beat.
+    Exponential,
+}
+
+/// Tempo changes over time in quarter notes per minute, converting between beat times and seconds
+/// by integrating the tempo exactly rather than step by step.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TempoMap {
+    changes: Vec<(BeatTime, f64, TempoRamp)>,
+}
+
+/// How hard a drum is struck.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DrumHit {
+    Accent,
+    Normal,
+    /// https://en.wikipedia.org/wiki/Ghost_note
+    Ghost,
+}
+
+/// https://en.wikipedia.org/wiki/Drum_machine#Programming
+///
+/// A rhythm on a grid of equal steps, as in a step sequencer, with how hard each of its notes is
+/// struck. Written one symbol per step, e.g. `X--x-o..`, where `X`, `x` and `o` strike an accent, a
+/// normal hit and a ghost note, `-` strikes nothing, letting the note before ring on, and `.` rests.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DrumGrid {
+    step: BeatDuration,
+    rhythm: Rhythm,
+    hits: Vec<DrumHit>,
+}
@@ -1,9 +1,401 @@
-use super::{BeatDuration, Rhythm};
+use super::{BeatDuration, BeatTime, Rhythm, RhythmEmbellishment, RhythmEvent, Subdivision};
+
+impl RhythmEmbellishment {
+    pub const ALL: [RhythmEmbellishment; 2] =
+        [RhythmEmbellishment::Roll, RhythmEmbellishment::Anticipation];
+}
 
 impl Rhythm {
+    /// A single note lasting `bd`.
     pub fn new(bd: BeatDuration) -> Rhythm {
+        Rhythm::from_durations(&[bd])
+    }
+
+    /// A note on each of `durations`.
+    pub fn from_durations(durations: &[BeatDuration]) -> Rhythm {
         Rhythm {
-            durations: vec![bd],
+            durations: durations.to_vec(),
+            events: vec![RhythmEvent::Onset; durations.len()],
         }
     }
+
+    /// A rest lasting `bd`.
+    pub fn rest(bd: BeatDuration) -> Rhythm {
+        let mut rhythm = Rhythm::default();
+        rhythm.push(RhythmEvent::Rest, bd);
+        rhythm
+    }
+
+    pub fn push(&mut self, event: RhythmEvent, duration: BeatDuration) {
+        self.events.push(event);
+        self.durations.push(duration);
+    }
+
+    pub fn len(&self) -> usize {
+        self.durations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.durations.is_empty()
+    }
+
+    pub fn durations(&self) -> &[BeatDuration] {
+        &self.durations
+    }
+
+    pub fn events(&self) -> &[RhythmEvent] {
+        &self.events
+    }
+
+    /// How long the rhythm lasts, rests included.
+    pub fn length(&self) -> BeatDuration {
+        self.durations
+            .iter()
+            .fold(BeatDuration::default(), |total, &d| total + d)
+    }
+
+    /// When each event starts.
+    pub fn starts(&self) -> Vec<BeatTime> {
+        let mut start = BeatTime::zero();
+        self.durations
+            .iter()
+            .map(|&d| {
+                let time = start;
+                start += d;
+                time
+            })
+            .collect()
+    }
+
+    /// Each note as when it starts and how long it sounds, ties included. A tie with no note
+    /// before it starts a note of its own.
+    pub fn notes(&self) -> Vec<(BeatTime, BeatDuration)> {
+        let mut notes: Vec<(BeatTime, BeatDuration)> = Vec::new();
+        let mut sounding = false;
+        for ((&event, &duration), start) in self
+            .events
+            .iter()
+            .zip(self.durations.iter())
+            .zip(self.starts())
+        {
+            match event {
+                RhythmEvent::Tie if sounding => {
+                    let last = notes.last_mut().unwrap();
+                    last.1 = last.1 + duration;
+                }
+                RhythmEvent::Onset | RhythmEvent::Tie => {
+                    notes.push((start, duration));
+                    sounding = true;
+                }
+                RhythmEvent::Rest => sounding = false,
+            }
+        }
+        notes
+    }
+
+    /// When each note starts.
+    pub fn onsets(&self) -> Vec<BeatTime> {
+        self.notes().into_iter().map(|(start, _)| start).collect()
+    }
+
+    /// `other` played after this.
+    pub fn concat(&self, other: &Rhythm) -> Rhythm {
+        let mut rhythm = self.clone();
+        rhythm.durations.extend_from_slice(&other.durations);
+        rhythm.events.extend_from_slice(&other.events);
+        rhythm
+    }
+
+    /// The event at `ix` split by `sub`, or None if its duration does not divide evenly. The parts
+    /// of a note each start a note of their own, while rests stay rests and a tie only carries over
+    /// into its first part.
+    pub fn subdivided_at(&self, ix: usize, sub: &Subdivision) -> Option<Rhythm> {
+        let parts = self.durations[ix].subdivided(sub.clone())?;
+        let event = self.events[ix];
+        let mut rhythm = Rhythm::default();
+        for i in 0..self.len() {
+            if i != ix {
+                rhythm.push(self.events[i], self.durations[i]);
+                continue;
+            }
+            for (j, &part) in parts.iter().enumerate() {
+                let part_event = match event {
+                    RhythmEvent::Rest => RhythmEvent::Rest,
+                    RhythmEvent::Tie if j == 0 => RhythmEvent::Tie,
+                    _ => RhythmEvent::Onset,
+                };
+                rhythm.push(part_event, part);
+            }
+        }
+        Some(rhythm)
+    }
+
+    /// Every event split by `sub`, see `subdivided_at`. Subdividing again splits the parts in turn.
+    pub fn subdivided(&self, sub: &Subdivision) -> Option<Rhythm> {
+        (0..self.len())
+            .rev()
+            .try_fold(self.clone(), |rhythm, ix| rhythm.subdivided_at(ix, sub))
+    }
+
+    /// Every duration multiplied by `numerator` and divided by `denominator`, e.g. `(2, 1)` for
+    /// augmentation and `(1, 2)` for diminution, or None where a duration does not divide evenly.
+    pub fn scaled(&self, numerator: u64, denominator: u64) -> Option<Rhythm> {
+        assert!(numerator > 0 && denominator > 0);
+        let durations = self
+            .durations
+            .iter()
+            .map(|&d| d * numerator)
+            .map(|d| {
+                if d % denominator == 0 {
+                    Some(d / denominator)
+                } else {
+                    None
+                }
+            })
+            .collect::<Option<_>>()?;
+        Some(Rhythm {
+            durations,
+            ..self.clone()
+        })
+    }
+
+    /// The notes and rests in reverse order, each note keeping its ties. A tie starting a note of
+    /// its own becomes an onset, so as not to tie over to the note now before it.
+    pub fn reversed(&self) -> Rhythm {
+        let mut groups: Vec<Rhythm> = Vec::new();
+        for (&event, &duration) in self.events.iter().zip(self.durations.iter()) {
+            match groups.last_mut() {
+                Some(group)
+                    if event == RhythmEvent::Tie && group.events[0] != RhythmEvent::Rest =>
+                {
+                    group.push(event, duration)
+                }
+                _ => {
+                    let event = match event {
+                        RhythmEvent::Tie => RhythmEvent::Onset,
+                        _ => event,
+                    };
+                    let mut group = Rhythm::default();
+                    group.push(event, duration);
+                    groups.push(group);
+                }
+            }
+        }
+        groups
+            .iter()
+            .rev()
+            .fold(Rhythm::default(), |rhythm, group| rhythm.concat(group))
+    }
+
+    /// The `len` events starting at the event at `start`.
+    pub fn fragment(&self, start: usize, len: usize) -> Rhythm {
+        Rhythm {
+            durations: self.durations[start..start + len].to_vec(),
+            events: self.events[start..start + len].to_vec(),
+        }
+    }
+
+    /// The events moved `n` places earlier, those falling off the start coming round to the end.
+    pub fn rotated(&self, n: usize) -> Rhythm {
+        let mut rhythm = self.clone();
+        if !self.is_empty() {
+            rhythm.durations.rotate_left(n % self.len());
+            rhythm.events.rotate_left(n % self.len());
+        }
+        rhythm
+    }
+
+    /// The note starting at `ix` embellished on a grid of `step`, or None unless it lasts at least
+    /// two whole steps.
+    pub fn embellished(
+        &self,
+        ix: usize,
+        step: BeatDuration,
+        embellishment: RhythmEmbellishment,
+    ) -> Option<Rhythm> {
+        let duration = self.durations[ix];
+        if self.events[ix] != RhythmEvent::Onset
+            || step == BeatDuration::default()
+            || duration % step.0 != 0
+            || duration / step < 2
+        {
+            return None;
+        }
+        let steps = duration / step;
+        let mut replacement = Rhythm::default();
+        match embellishment {
+            RhythmEmbellishment::Roll => {
+                for _ in 0..steps - 1 {
+                    replacement.push(RhythmEvent::Onset, step);
+                }
+                replacement.push(RhythmEvent::Tie, step);
+            }
+            RhythmEmbellishment::Anticipation => {
+                replacement.push(RhythmEvent::Onset, step * (steps - 1));
+                replacement.push(RhythmEvent::Onset, step);
+            }
+        }
+        let mut rhythm = Rhythm::default();
+        for i in 0..self.len() {
+            if i == ix {
+                rhythm = rhythm.concat(&replacement);
+            } else {
+                rhythm.push(self.events[i], self.durations[i]);
+            }
+        }
+        Some(rhythm)
+    }
+}
+
+/******************************************************************************
+* TESTS
+******************************************************************************/
+
+#[cfg(test)]
+mod tests {
+    use super::super::{EN, QN, SN};
+    use super::*;
+
+    fn at(d: BeatDuration) -> BeatTime {
+        BeatTime::zero() + d
+    }
+
+    /// A quarter note tied over an eighth, an eighth rest, a tie after the rest starting a quarter
+    /// note of its own, then an eighth note tied to another.
+    fn tied() -> Rhythm {
+        let mut rhythm = Rhythm::new(QN);
+        rhythm.push(RhythmEvent::Tie, EN);
+        rhythm.push(RhythmEvent::Rest, EN);
+        rhythm.push(RhythmEvent::Tie, QN);
+        rhythm.push(RhythmEvent::Onset, EN);
+        rhythm.push(RhythmEvent::Tie, EN);
+        rhythm
+    }
+
+    #[test]
+    fn notes_through_ties_and_rests() {
+        let rhythm = tied();
+        assert_eq!(
+            rhythm.notes(),
+            [
+                (at(BeatDuration::default()), QN + EN),
+                (at(QN * 2), QN),
+                (at(QN * 3), QN),
+            ]
+        );
+        assert_eq!(rhythm.length(), QN * 4);
+        assert_eq!(rhythm.starts().len(), 6);
+
+        let mut leading = Rhythm::default();
+        leading.push(RhythmEvent::Tie, QN);
+        leading.push(RhythmEvent::Tie, QN);
+        assert_eq!(leading.notes(), [(at(BeatDuration::default()), QN * 2)]);
+        assert_eq!(Rhythm::rest(QN).notes(), []);
+    }
+
+    #[test]
+    fn reversal_keeps_ties() {
+        let reversed = tied().reversed();
+        assert_eq!(
+            reversed.notes(),
+            [
+                (at(BeatDuration::default()), QN),
+                (at(QN), QN),
+                (at(QN * 2 + EN), QN + EN),
+            ]
+        );
+        assert_eq!(reversed.reversed().notes(), tied().notes());
+        assert_eq!(reversed.length(), tied().length());
+    }
+
+    #[test]
+    fn subdivisions_split_notes_and_rests() {
+        let mut rhythm = Rhythm::new(QN);
+        rhythm.push(RhythmEvent::Tie, QN);
+        rhythm.push(RhythmEvent::Rest, QN);
+        let halves = rhythm.subdivided(&Subdivision::new(&vec![1, 1])).unwrap();
+        assert_eq!(
+            halves.events(),
+            [
+                RhythmEvent::Onset,
+                RhythmEvent::Onset,
+                RhythmEvent::Tie,
+                RhythmEvent::Onset,
+                RhythmEvent::Rest,
+                RhythmEvent::Rest,
+            ]
+        );
+        assert_eq!(halves.durations(), [EN; 6]);
+        assert_eq!(
+            halves.onsets(),
+            [at(BeatDuration::default()), at(EN), at(EN * 3)]
+        );
+        let first = rhythm
+            .subdivided_at(0, &Subdivision::new(&vec![2, 1]))
+            .unwrap();
+        assert_eq!(first.durations(), [QN * 2 / 3, QN / 3, QN, QN]);
+        let sevenths = Subdivision::new(&vec![1; 7]);
+        assert_eq!(rhythm.subdivided(&sevenths), None);
+    }
+
+    #[test]
+    fn scaling_only_when_even() {
+        let rhythm = Rhythm::from_durations(&[QN, EN]).concat(&Rhythm::rest(SN));
+        let augmented = rhythm.scaled(2, 1).unwrap();
+        assert_eq!(augmented.durations(), [QN * 2, QN, EN]);
+        assert_eq!(augmented.events(), rhythm.events());
+        assert_eq!(
+            rhythm.scaled(3, 2).unwrap().durations(),
+            [QN * 3 / 2, SN * 3, SN * 3 / 2]
+        );
+        assert_eq!(rhythm.scaled(1, 7), None);
+    }
+
+    #[test]
+    fn rotations_wrap_around() {
+        let rhythm = Rhythm::from_durations(&[QN, EN]).concat(&Rhythm::rest(EN));
+        let rotated = rhythm.rotated(1);
+        assert_eq!(rotated.durations(), [EN, EN, QN]);
+        assert_eq!(
+            rotated.events(),
+            [RhythmEvent::Onset, RhythmEvent::Rest, RhythmEvent::Onset]
+        );
+        assert_eq!(rhythm.rotated(4), rotated);
+        assert_eq!(rhythm.rotated(3), rhythm);
+        assert_eq!(Rhythm::default().rotated(2), Rhythm::default());
+    }
+
+    #[test]
+    fn embellishments_of_a_long_note() {
+        let rhythm = Rhythm::new(QN).concat(&Rhythm::rest(QN));
+        let roll = rhythm
+            .embellished(0, SN, RhythmEmbellishment::Roll)
+            .unwrap();
+        assert_eq!(roll.durations(), [SN, SN, SN, SN, QN]);
+        assert_eq!(
+            roll.events(),
+            [
+                RhythmEvent::Onset,
+                RhythmEvent::Onset,
+                RhythmEvent::Onset,
+                RhythmEvent::Tie,
+                RhythmEvent::Rest,
+            ]
+        );
+        assert_eq!(roll.notes().last(), Some(&(at(SN * 2), EN)));
+        let anticipation = rhythm
+            .embellished(0, SN, RhythmEmbellishment::Anticipation)
+            .unwrap();
+        assert_eq!(
+            anticipation.onsets(),
+            [at(BeatDuration::default()), at(SN * 3)]
+        );
+        assert_eq!(anticipation.length(), rhythm.length());
+        assert_eq!(rhythm.embellished(1, SN, RhythmEmbellishment::Roll), None);
+        assert_eq!(rhythm.embellished(0, QN, RhythmEmbellishment::Roll), None);
+        assert_eq!(
+            rhythm.embellished(0, EN * 3 / 2, RhythmEmbellishment::Roll),
+            None
+        );
+    }
 }
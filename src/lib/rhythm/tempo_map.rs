@@ -0,0 +1,216 @@
+use super::{BeatTime, TempoMap, TempoRamp, DURATION_MULTIPLIER};
+
+/// Tempo differences below this many beats per minute count as none, avoiding division by zero.
+const TEMPO_EPSILON: f64 = 1e-9;
+
+/// Beat times this close below a whole tick count as reaching it, absorbing rounding errors.
+const TICK_EPSILON: f64 = 1e-6;
+
+impl TempoMap {
+    /// A constant tempo of `bpm` quarter notes per minute.
+    pub fn new(bpm: f64) -> TempoMap {
+        assert!(bpm > 0.);
+        TempoMap {
+            changes: vec![(BeatTime::zero(), bpm, TempoRamp::Instant)],
+        }
+    }
+
+    /// Reaches `bpm` at `time` by `ramp` from the tempo of the change before, replacing any change
+    /// already at `time`.
+    pub fn with_change(&self, time: BeatTime, bpm: f64, ramp: TempoRamp) -> TempoMap {
+        assert!(bpm > 0.);
+        let mut map = self.clone();
+        map.changes.retain(|&(t, _, _)| t != time);
+        map.changes.push((time, bpm, ramp));
+        map.changes.sort_by_key(|&(t, _, _)| t);
+        if map.changes[0].0 != BeatTime::zero() {
+            map.changes.insert(0, self.changes[0]);
+        }
+        map
+    }
+
+    /// Each tempo change with the time it is reached at and how.
+    pub fn changes(&self) -> &[(BeatTime, f64, TempoRamp)] {
+        &self.changes
+    }
+
+    /// The tempo at `time` in quarter notes per minute.
+    pub fn bpm(&self, time: BeatTime) -> f64 {
+        let ix = self
+            .changes
+            .iter()
+            .rposition(|&(t, _, _)| t <= time)
+            .unwrap_or(0);
+        let (from_time, from_bpm, _) = self.changes[ix];
+        match self.changes.get(ix + 1) {
+            Some(&(to_time, to_bpm, ramp)) if ramp != TempoRamp::Instant => {
+                let t = f64::from(time - from_time) / f64::from(to_time - from_time);
+                match ramp {
+                    TempoRamp::Exponential => from_bpm * (to_bpm / from_bpm).powf(t),
+                    _ => from_bpm + (to_bpm - from_bpm) * t,
+                }
+            }
+            _ => from_bpm,
+        }
+    }
+
+    /// Seconds from time zero to `time`.
+    pub fn seconds(&self, time: BeatTime) -> f64 {
+        self.seconds_at(f64::from(time))
+    }
+
+    /// Seconds from time zero to a fractional beat time, as counted by `BeatTime`.
+    pub fn seconds_at(&self, beat: f64) -> f64 {
+        let mut seconds = 0.;
+        for (i, &(time, bpm, _)) in self.changes.iter().enumerate() {
+            let start = f64::from(time);
+            match self.changes.get(i + 1) {
+                Some(&(next_time, next_bpm, ramp)) if f64::from(next_time) < beat => {
+                    let quarters = quarters(f64::from(next_time) - start);
+                    seconds += ramp_seconds(bpm, next_bpm, ramp, quarters, quarters);
+                }
+                Some(&(next_time, next_bpm, ramp)) => {
+                    let span = quarters(f64::from(next_time) - start);
+                    return seconds
+                        + ramp_seconds(bpm, next_bpm, ramp, span, quarters(beat - start));
+                }
+                None => return seconds + 60. * quarters(beat - start) / bpm,
+            }
+        }
+        unreachable!()
+    }
+
+    /// The fractional beat time, as counted by `BeatTime`, reached after `seconds`.
+    pub fn beat_at(&self, seconds: f64) -> f64 {
+        let mut elapsed = 0.;
+        for (i, &(time, bpm, _)) in self.changes.iter().enumerate() {
+            let start = f64::from(time);
+            match self.changes.get(i + 1) {
+                Some(&(next_time, next_bpm, ramp)) => {
+                    let span = quarters(f64::from(next_time) - start);
+                    let duration = ramp_seconds(bpm, next_bpm, ramp, span, span);
+                    if elapsed + duration > seconds {
+                        let q = ramp_quarters(bpm, next_bpm, ramp, span, seconds - elapsed);
+                        return start + q * f64::from(DURATION_MULTIPLIER);
+                    }
+                    elapsed += duration;
+                }
+                None => {
+                    let q = (seconds - elapsed) * bpm / 60.;
+                    return start + q * f64::from(DURATION_MULTIPLIER);
+                }
+            }
+        }
+        unreachable!()
+    }
+
+    /// The beat time reached after `seconds`, rounded down to a whole tick, so that it gives back
+    /// the time of `seconds(time)`.
+    pub fn time_at(&self, seconds: f64) -> BeatTime {
+        BeatTime::from(self.beat_at(seconds) + TICK_EPSILON)
+    }
+
+    /// The sample playing at `time`, counting from 0 at time zero.
+    pub fn sample(&self, time: BeatTime, sample_rate: f64) -> u64 {
+        (self.seconds(time) * sample_rate).round() as u64
+    }
+
+    /// The fractional beat time at `sample`.
+    pub fn beat_at_sample(&self, sample: u64, sample_rate: f64) -> f64 {
+        self.beat_at(sample as f64 / sample_rate)
+    }
+}
+
+impl Default for TempoMap {
+    fn default() -> Self {
+        TempoMap::new(120.)
+    }
+}
+
+fn quarters(ticks: f64) -> f64 {
+    ticks / f64::from(DURATION_MULTIPLIER)
+}
+
+/// Seconds taken by the first `q` of `span` quarter notes of a ramp from `from` to `to` bpm.
+fn ramp_seconds(from: f64, to: f64, ramp: TempoRamp, span: f64, q: f64) -> f64 {
+    if ramp == TempoRamp::Instant || (to - from).abs() < TEMPO_EPSILON {
+        return 60. * q / from;
+    }
+    match ramp {
+        TempoRamp::Exponential => {
+            let ln_ratio = (to / from).ln();
+            60. * span / (from * ln_ratio) * (1. - (-ln_ratio * q / span).exp())
+        }
+        _ => {
+            let slope = (to - from) / span;
+            60. / slope * ((from + slope * q) / from).ln()
+        }
+    }
+}
+
+/// Quarter notes played in the first `seconds` of a ramp from `from` to `to` bpm over `span`
+/// quarter notes, the inverse of `ramp_seconds`.
+fn ramp_quarters(from: f64, to: f64, ramp: TempoRamp, span: f64, seconds: f64) -> f64 {
+    if ramp == TempoRamp::Instant || (to - from).abs() < TEMPO_EPSILON {
+        return seconds * from / 60.;
+    }
+    match ramp {
+        TempoRamp::Exponential => {
+            let ln_ratio = (to / from).ln();
+            -span / ln_ratio * (1. - seconds * from * ln_ratio / (60. * span)).ln()
+        }
+        _ => {
+            let slope = (to - from) / span;
+            from / slope * ((seconds * slope / 60.).exp() - 1.)
+        }
+    }
+}
+
+/******************************************************************************
+* TESTS
+******************************************************************************/
+
+#[cfg(test)]
+mod tests {
+    use super::super::{QN, WN};
+    use super::*;
+
+    fn map() -> TempoMap {
+        TempoMap::new(120.)
+            .with_change(BeatTime::zero() + WN * 2, 90., TempoRamp::Instant)
+            .with_change(BeatTime::zero() + WN * 4, 150., TempoRamp::Linear)
+            .with_change(BeatTime::zero() + WN * 6, 60., TempoRamp::Exponential)
+    }
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-9, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn seconds_and_beats_are_inverse() {
+        let map = map();
+        for i in 0..400 {
+            let seconds = i as f64 * 0.05;
+            assert_close(map.seconds_at(map.beat_at(seconds)), seconds);
+        }
+        let mut time = BeatTime::zero();
+        while time < BeatTime::zero() + WN * 8 {
+            assert_close(map.beat_at(map.seconds(time)), f64::from(time));
+            assert_eq!(map.time_at(map.seconds(time)), time);
+            time += QN / 3;
+        }
+    }
+
+    #[test]
+    fn seconds_and_tempi_of_ramps() {
+        let map = map();
+        let at = |bars: u64| map.seconds(BeatTime::zero() + WN * bars);
+        assert_close(at(1), 2.);
+        assert_close(at(2), 4.);
+        // Eight quarters from 90 to 150 linearly in beats take 60 * 8 / (150 - 90) * ln(150 / 90).
+        assert_close(at(4), 4. + 8. * (5f64 / 3.).ln());
+        assert_close(map.bpm(BeatTime::zero() + WN * 3), 120.);
+        assert_close(map.bpm(BeatTime::zero() + WN * 5), (150f64 * 60.).sqrt());
+        assert_close(map.bpm(BeatTime::zero() + WN * 8), 60.);
+    }
+}
@@ -0,0 +1,170 @@
+use super::{BeatDuration, Meter, TimeSignature, WN};
+
+/******************************************************************************
+* CONSTANTS
+******************************************************************************/
+
+pub const COMMON_TIME: TimeSignature = TimeSignature {
+    numerator: 4,
+    denominator: 4,
+};
+
+/******************************************************************************
+* ERRORS
+******************************************************************************/
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TimeSignatureError {
+    InvalidFormat(String),
+    ZeroNumerator,
+    InvalidDenominator(u64),
+}
+
+impl std::fmt::Display for TimeSignatureError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            TimeSignatureError::InvalidFormat(s) => {
+                write!(f, "invalid time signature \"{}\", expected e.g. 7/8", s)
+            }
+            TimeSignatureError::ZeroNumerator => write!(f, "a bar needs at least one beat"),
+            TimeSignatureError::InvalidDenominator(d) => write!(
+                f,
+                "the denominator must be a power of two up to {}, found {}",
+                MAX_DENOMINATOR, d
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TimeSignatureError {}
+
+/******************************************************************************
+* IMPLS
+******************************************************************************/
+
+/// The shortest beat that is a whole number of ticks.
+const MAX_DENOMINATOR: u64 = 32;
+
+impl TimeSignature {
+    pub fn new(numerator: u64, denominator: u64) -> Result<TimeSignature, TimeSignatureError> {
+        if numerator == 0 {
+            return Err(TimeSignatureError::ZeroNumerator);
+        }
+        if !denominator.is_power_of_two() || denominator > MAX_DENOMINATOR {
+            return Err(TimeSignatureError::InvalidDenominator(denominator));
+        }
+        Ok(TimeSignature {
+            numerator,
+            denominator,
+        })
+    }
+
+    pub fn numerator(self) -> u64 {
+        self.numerator
+    }
+
+    pub fn denominator(self) -> u64 {
+        self.denominator
+    }
+
+    /// How long each of the beats counted by the numerator lasts, e.g. an eighth note in 6/8.
+    pub fn beat(self) -> BeatDuration {
+        WN / self.denominator
+    }
+
+    pub fn bar(self) -> BeatDuration {
+        self.beat() * self.numerator
+    }
+
+    /// Compound for multiples of three eighths or shorter, e.g. 6/8 as two dotted quarters, groups
+    /// of two and a final three for other odd numerators, e.g. 7/8 as 2+2+3, and simple otherwise.
+    pub fn meter(self) -> Meter {
+        let n = self.numerator;
+        if self.denominator >= 8 && n > 3 && n.is_multiple_of(3) {
+            Meter::compound(n / 3, self.beat())
+        } else if n > 3 && !n.is_multiple_of(2) {
+            let mut groups = vec![2; (n as usize - 3) / 2];
+            groups.push(3);
+            Meter::additive(&groups, self.beat())
+        } else {
+            Meter::simple(n, self.beat())
+        }
+    }
+}
+
+impl Default for TimeSignature {
+    fn default() -> Self {
+        COMMON_TIME
+    }
+}
+
+impl std::fmt::Display for TimeSignature {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}/{}", self.numerator, self.denominator)
+    }
+}
+
+/// Parses e.g. `7/8`.
+impl std::str::FromStr for TimeSignature {
+    type Err = TimeSignatureError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || TimeSignatureError::InvalidFormat(s.to_string());
+        let mut parts = s.trim().splitn(2, '/');
+        let numerator = parts.next().ok_or_else(invalid)?;
+        let denominator = parts.next().ok_or_else(invalid)?;
+        TimeSignature::new(
+            numerator.trim().parse().map_err(|_| invalid())?,
+            denominator.trim().parse().map_err(|_| invalid())?,
+        )
+    }
+}
+
+/******************************************************************************
+* TESTS
+******************************************************************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signatures_round_trip() {
+        for &s in ["4/4", "3/4", "6/8", "7/8", "12/8", "5/16", "1/1", "9/32"].iter() {
+            let signature: TimeSignature = s.parse().unwrap();
+            assert_eq!(signature.to_string(), s);
+        }
+        assert_eq!(" 3 / 4 ".parse(), TimeSignature::new(3, 4));
+    }
+
+    #[test]
+    fn invalid_signatures() {
+        assert_eq!(
+            "0/4".parse::<TimeSignature>(),
+            Err(TimeSignatureError::ZeroNumerator)
+        );
+        assert_eq!(
+            "3/6".parse::<TimeSignature>(),
+            Err(TimeSignatureError::InvalidDenominator(6))
+        );
+        assert_eq!(
+            "3/64".parse::<TimeSignature>(),
+            Err(TimeSignatureError::InvalidDenominator(64))
+        );
+        for &s in ["", "3", "3/", "/4", "a/4", "3/4/4"].iter() {
+            assert!(s.parse::<TimeSignature>().is_err(), "{}", s);
+        }
+    }
+
+    #[test]
+    fn meter_of_every_accepted_signature() {
+        let mut denominator = 1;
+        while denominator <= MAX_DENOMINATOR {
+            for numerator in 1..=16 {
+                let signature = TimeSignature::new(numerator, denominator).unwrap();
+                assert_eq!(signature.meter().bar(), signature.bar(), "{}", signature);
+            }
+            denominator *= 2;
+        }
+    }
+}
@@ -0,0 +1,219 @@
+use super::{BarPosition, BeatDuration, BeatTime, TimeSignature, TimeSignatureMap};
+
+impl TimeSignatureMap {
+    pub fn new(signature: TimeSignature) -> TimeSignatureMap {
+        TimeSignatureMap {
+            signatures: vec![(0, signature)],
+            pickup: None,
+        }
+    }
+
+    /// Starts with a pickup bar of `length`, shorter than a full bar of the first time signature.
+    pub fn with_pickup(&self, length: BeatDuration) -> TimeSignatureMap {
+        assert!(length > BeatDuration::default() && length < self.signatures[0].1.bar());
+        TimeSignatureMap {
+            pickup: Some(length),
+            ..self.clone()
+        }
+    }
+
+    /// Changes to `signature` from `bar` on, replacing any change already there.
+    ///
+    /// Panics if a pickup would no longer be shorter than a full bar of the first time signature.
+    pub fn with_change(&self, bar: usize, signature: TimeSignature) -> TimeSignatureMap {
+        let mut map = self.clone();
+        map.signatures.retain(|&(b, _)| b != bar);
+        map.signatures.push((bar, signature));
+        map.signatures.sort_by_key(|&(b, _)| b);
+        if map.signatures[0].0 != 0 {
+            map.signatures.insert(0, (0, self.signatures[0].1));
+        }
+        if let Some(pickup) = map.pickup {
+            assert!(pickup < map.signatures[0].1.bar());
+        }
+        map
+    }
+
+    /// The time signature changes, each from the bar it takes effect in.
+    pub fn signatures(&self) -> &[(usize, TimeSignature)] {
+        &self.signatures
+    }
+
+    pub fn pickup(&self) -> Option<BeatDuration> {
+        self.pickup
+    }
+
+    /// Whether `bar` is the pickup.
+    pub fn is_pickup(&self, bar: usize) -> bool {
+        bar == 0 && self.pickup.is_some()
+    }
+
+    /// The time signature in effect in `bar`, a pickup taking the first one.
+    pub fn signature(&self, bar: usize) -> TimeSignature {
+        self.signatures
+            .iter()
+            .rev()
+            .find(|&&(b, _)| b <= bar)
+            .map(|&(_, signature)| signature)
+            .unwrap()
+    }
+
+    pub fn bar_length(&self, bar: usize) -> BeatDuration {
+        match self.pickup {
+            Some(pickup) if bar == 0 => pickup,
+            _ => self.signature(bar).bar(),
+        }
+    }
+
+    /// When `bar` starts.
+    pub fn bar_start(&self, bar: usize) -> BeatTime {
+        self.segments(|first_bar, next_bar, start, length| {
+            if bar < next_bar {
+                Some(start + length * (bar - first_bar) as u64)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// The bar sounding at `time`.
+    pub fn bar_at(&self, time: BeatTime) -> usize {
+        self.segments(|first_bar, next_bar, start, length| {
+            if time < start {
+                return None;
+            }
+            let bar = first_bar + ((time - start).0 / length.0) as usize;
+            if bar < next_bar {
+                Some(bar)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// How far into its bar `time` falls, counting a pickup as the end of a full bar.
+    pub fn offset_in_bar(&self, time: BeatTime) -> BeatDuration {
+        let bar = self.bar_at(time);
+        let offset = time - self.bar_start(bar);
+        match self.pickup {
+            Some(pickup) if bar == 0 => offset + (self.signature(0).bar() - pickup),
+            _ => offset,
+        }
+    }
+
+    pub fn position(&self, time: BeatTime) -> BarPosition {
+        let bar = self.bar_at(time);
+        let offset = self.offset_in_bar(time);
+        let beat = self.signature(bar).beat();
+        BarPosition::new(bar, offset.0 / beat.0, BeatDuration(offset.0 % beat.0))
+    }
+
+    /// The time at `position`, or None if it lies outside its bar, e.g. beat 3 of 3/4 counting from
+    /// 0, or before the start of a pickup.
+    pub fn time(&self, position: BarPosition) -> Option<BeatTime> {
+        let signature = self.signature(position.bar());
+        if position.beat() >= signature.numerator() || position.tick() >= signature.beat() {
+            return None;
+        }
+        let offset = signature.beat() * position.beat() + position.tick();
+        match self.pickup {
+            Some(pickup) if position.bar() == 0 => {
+                let skipped = signature.bar() - pickup;
+                if offset < skipped {
+                    return None;
+                }
+                Some(BeatTime::zero() + (offset - skipped))
+            }
+            _ => Some(self.bar_start(position.bar()) + offset),
+        }
+    }
+
+    /// The metric emphasis of `time` in the meter of its bar, see `Meter::emphasis`.
+    pub fn emphasis(&self, time: BeatTime) -> f64 {
+        let meter = self.signature(self.bar_at(time)).meter();
+        meter.emphasis(BeatTime::zero() + self.offset_in_bar(time))
+    }
+
+    /// Calls `f` with the first bar, the bar after the last one, the start time and the bar length
+    /// of each stretch of equal bars in turn, the pickup first, until it returns a value.
+    fn segments<T, F>(&self, mut f: F) -> T
+    where
+        F: FnMut(usize, usize, BeatTime, BeatDuration) -> Option<T>,
+    {
+        let mut start = BeatTime::zero();
+        let mut first_full_bar = 0;
+        if let Some(pickup) = self.pickup {
+            if let Some(value) = f(0, 1, start, pickup) {
+                return value;
+            }
+            start += pickup;
+            first_full_bar = 1;
+        }
+        for (i, &(bar, signature)) in self.signatures.iter().enumerate() {
+            let first_bar = bar.max(first_full_bar);
+            let next_bar = match self.signatures.get(i + 1) {
+                Some(&(next, _)) => next.max(first_bar),
+                None => usize::MAX,
+            };
+            if let Some(value) = f(first_bar, next_bar, start, signature.bar()) {
+                return value;
+            }
+            start += signature.bar() * (next_bar - first_bar) as u64;
+        }
+        unreachable!()
+    }
+}
+
+impl Default for TimeSignatureMap {
+    fn default() -> Self {
+        TimeSignatureMap::new(TimeSignature::default())
+    }
+}
+
+/******************************************************************************
+* TESTS
+******************************************************************************/
+
+#[cfg(test)]
+mod tests {
+    use super::super::{COMMON_TIME, HN, QN, SN};
+    use super::*;
+
+    fn map() -> TimeSignatureMap {
+        TimeSignatureMap::new(COMMON_TIME)
+            .with_pickup(QN)
+            .with_change(3, "7/8".parse().unwrap())
+            .with_change(5, "3/4".parse().unwrap())
+    }
+
+    #[test]
+    fn positions_round_trip() {
+        let map = map();
+        let mut time = BeatTime::zero();
+        while map.bar_at(time) < 8 {
+            assert_eq!(map.time(map.position(time)), Some(time), "{:?}", time);
+            time += SN;
+        }
+    }
+
+    #[test]
+    fn positions_of_bars() {
+        let map = map();
+        let position = |quarters: u64| map.position(BeatTime::zero() + QN * quarters).to_string();
+        assert_eq!(position(0), "0:3:0");
+        assert_eq!(position(1), "1:0:0");
+        assert_eq!(position(9), "3:0:0");
+        assert_eq!(map.bar_length(3), QN * 7 / 2);
+        assert_eq!(map.position(map.bar_start(5)).to_string(), "5:0:0");
+        assert_eq!(map.time(BarPosition::new(0, 2, BeatDuration(0))), None);
+        assert_eq!(map.time(BarPosition::new(5, 3, BeatDuration(0))), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn pickups_outlasting_a_new_first_bar() {
+        TimeSignatureMap::new(COMMON_TIME)
+            .with_pickup(HN)
+            .with_change(0, "1/4".parse().unwrap());
+    }
+}
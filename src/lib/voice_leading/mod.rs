@@ -0,0 +1,77 @@
+mod violation;
+mod voice_leader;
+mod voicing;
+
+use super::{ChromaticRange, Note};
+
+pub const VOICE_COUNT: usize = 4;
+
+/// https://en.wikipedia.org/wiki/SATB
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Voice {
+    Soprano,
+    Alto,
+    Tenor,
+    Bass,
+}
+
+/// One note per voice, indexed by `Voice` from the soprano down.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Voicing([Note; VOICE_COUNT]);
+
+/// The rules of the README's harmony section, plus the chord completeness and spacing rules any
+/// four part voicing is held to.
+///
+/// https://en.wikipedia.org/wiki/Voice_leading#Common-practice_conventions_and_pedagogy
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum VoiceLeadingRule {
+    CommonToneNotRetained,
+    NotShortestWay,
+    NoContraryMotion,
+    VoiceCrossing,
+    ParallelFifths,
+    ParallelOctaves,
+    HiddenFifths,
+    HiddenOctaves,
+    WideSpacing,
+    MissingChordTone,
+    OmittedFifth,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Violation {
+    rule: VoiceLeadingRule,
+    voices: Vec<Voice>,
+}
+
+/// The cost of breaking each rule once, plus the cost of moving any voice by a semitone.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct VoiceLeadingWeights {
+    pub motion: f64,
+    pub common_tone_not_retained: f64,
+    pub not_shortest_way: f64,
+    pub no_contrary_motion: f64,
+    pub voice_crossing: f64,
+    pub parallel_fifths: f64,
+    pub parallel_octaves: f64,
+    pub hidden_fifths: f64,
+    pub hidden_octaves: f64,
+    pub wide_spacing: f64,
+    pub missing_chord_tone: f64,
+    pub omitted_fifth: f64,
+}
+
+/// Voices chord progressions in four parts.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct VoiceLeader {
+    ranges: [ChromaticRange; VOICE_COUNT],
+    weights: VoiceLeadingWeights,
+}
+
+/// The voicing of each chord, along with the rules broken by each voicing and the move into it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VoiceLeading {
+    voicings: Vec<Voicing>,
+    violations: Vec<Vec<Violation>>,
+    cost: f64,
+}
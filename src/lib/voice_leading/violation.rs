@@ -0,0 +1,159 @@
+use super::{Violation, Voice, VoiceLeadingRule, VoiceLeadingWeights};
+
+impl VoiceLeadingRule {
+    pub const ALL: [VoiceLeadingRule; 11] = [
+        VoiceLeadingRule::CommonToneNotRetained,
+        VoiceLeadingRule::NotShortestWay,
+        VoiceLeadingRule::NoContraryMotion,
+        VoiceLeadingRule::VoiceCrossing,
+        VoiceLeadingRule::ParallelFifths,
+        VoiceLeadingRule::ParallelOctaves,
+        VoiceLeadingRule::HiddenFifths,
+        VoiceLeadingRule::HiddenOctaves,
+        VoiceLeadingRule::WideSpacing,
+        VoiceLeadingRule::MissingChordTone,
+        VoiceLeadingRule::OmittedFifth,
+    ];
+}
+
+impl std::fmt::Display for VoiceLeadingRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(match self {
+            VoiceLeadingRule::CommonToneNotRetained => "common tone not retained",
+            VoiceLeadingRule::NotShortestWay => "not moving the shortest way",
+            VoiceLeadingRule::NoContraryMotion => "no contrary motion to the bass",
+            VoiceLeadingRule::VoiceCrossing => "voice crossing",
+            VoiceLeadingRule::ParallelFifths => "parallel fifths",
+            VoiceLeadingRule::ParallelOctaves => "parallel octaves",
+            VoiceLeadingRule::HiddenFifths => "hidden fifths",
+            VoiceLeadingRule::HiddenOctaves => "hidden octaves",
+            VoiceLeadingRule::WideSpacing => "more than an octave between upper voices",
+            VoiceLeadingRule::MissingChordTone => "missing chord tone",
+            VoiceLeadingRule::OmittedFifth => "omitted fifth",
+        })
+    }
+}
+
+impl Violation {
+    pub fn new(rule: VoiceLeadingRule, voices: &[Voice]) -> Violation {
+        Violation {
+            rule,
+            voices: voices.to_vec(),
+        }
+    }
+
+    pub fn rule(&self) -> VoiceLeadingRule {
+        self.rule
+    }
+
+    /// The voices breaking the rule, if it concerns particular voices.
+    pub fn voices(&self) -> &[Voice] {
+        &self.voices
+    }
+}
+
+/// Prints e.g. `parallel fifths (tenor, soprano)`.
+impl std::fmt::Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.rule)?;
+        for (i, voice) in self.voices.iter().enumerate() {
+            f.write_str(if i == 0 { " (" } else { ", " })?;
+            write!(f, "{}", voice)?;
+        }
+        if !self.voices.is_empty() {
+            f.write_str(")")?;
+        }
+        Ok(())
+    }
+}
+
+impl VoiceLeadingWeights {
+    pub fn weight(&self, rule: VoiceLeadingRule) -> f64 {
+        match rule {
+            VoiceLeadingRule::CommonToneNotRetained => self.common_tone_not_retained,
+            VoiceLeadingRule::NotShortestWay => self.not_shortest_way,
+            VoiceLeadingRule::NoContraryMotion => self.no_contrary_motion,
+            VoiceLeadingRule::VoiceCrossing => self.voice_crossing,
+            VoiceLeadingRule::ParallelFifths => self.parallel_fifths,
+            VoiceLeadingRule::ParallelOctaves => self.parallel_octaves,
+            VoiceLeadingRule::HiddenFifths => self.hidden_fifths,
+            VoiceLeadingRule::HiddenOctaves => self.hidden_octaves,
+            VoiceLeadingRule::WideSpacing => self.wide_spacing,
+            VoiceLeadingRule::MissingChordTone => self.missing_chord_tone,
+            VoiceLeadingRule::OmittedFifth => self.omitted_fifth,
+        }
+    }
+
+    pub fn cost(&self, violations: &[Violation]) -> f64 {
+        violations.iter().map(|v| self.weight(v.rule)).sum()
+    }
+}
+
+/// Parallels cost the most, then incomplete chords and crossings, while the README's preferences
+/// for common tones and short, contrary motion break ties between otherwise correct voicings.
+impl Default for VoiceLeadingWeights {
+    fn default() -> Self {
+        VoiceLeadingWeights {
+            motion: 1.0,
+            common_tone_not_retained: 4.0,
+            not_shortest_way: 3.0,
+            no_contrary_motion: 3.0,
+            voice_crossing: 25.0,
+            parallel_fifths: 60.0,
+            parallel_octaves: 80.0,
+            hidden_fifths: 8.0,
+            hidden_octaves: 10.0,
+            wide_spacing: 20.0,
+            missing_chord_tone: 40.0,
+            omitted_fifth: 6.0,
+        }
+    }
+}
+
+/******************************************************************************
+* TESTS
+******************************************************************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn violations_name_their_voices() {
+        let parallels = Violation::new(
+            VoiceLeadingRule::ParallelFifths,
+            &[Voice::Tenor, Voice::Soprano],
+        );
+        assert_eq!(parallels.to_string(), "parallel fifths (tenor, soprano)");
+        let omitted = Violation::new(VoiceLeadingRule::OmittedFifth, &[]);
+        assert_eq!(omitted.to_string(), "omitted fifth");
+    }
+
+    #[test]
+    fn costs_weigh_each_rule() {
+        let weights = VoiceLeadingWeights::default();
+        let violations = [
+            Violation::new(
+                VoiceLeadingRule::ParallelOctaves,
+                &[Voice::Bass, Voice::Alto],
+            ),
+            Violation::new(
+                VoiceLeadingRule::HiddenFifths,
+                &[Voice::Bass, Voice::Soprano],
+            ),
+            Violation::new(
+                VoiceLeadingRule::HiddenFifths,
+                &[Voice::Tenor, Voice::Soprano],
+            ),
+        ];
+        assert_eq!(weights.cost(&violations), 80. + 8. + 8.);
+        assert_eq!(weights.cost(&[]), 0.);
+        for &rule in VoiceLeadingRule::ALL.iter() {
+            assert!(weights.weight(rule) > 0., "{}", rule);
+        }
+        assert!(
+            weights.weight(VoiceLeadingRule::ParallelFifths)
+                > weights.weight(VoiceLeadingRule::HiddenFifths)
+        );
+    }
+}
@@ -0,0 +1,454 @@
+use super::super::chromatic_range_constants::{ALTO, BASS, SOPRANO, TENOR};
+use super::super::spelled_interval_constants::PER_5;
+use super::super::{Chord, ChromaticRange, Note, PitchClassSet, CHROMATIC_COUNT};
+use super::{
+    Violation, Voice, VoiceLeader, VoiceLeading, VoiceLeadingRule, VoiceLeadingWeights, Voicing,
+    VOICE_COUNT,
+};
+
+/// How many of the best voicings of each chord on its own are tried when leading voices.
+const CANDIDATE_COUNT: usize = 150;
+
+const UPPER_VOICES: [Voice; 3] = [Voice::Soprano, Voice::Alto, Voice::Tenor];
+
+impl VoiceLeader {
+    /// `ranges` are indexed by `Voice`, from the soprano down.
+    pub fn new(ranges: [ChromaticRange; VOICE_COUNT], weights: VoiceLeadingWeights) -> VoiceLeader {
+        VoiceLeader { ranges, weights }
+    }
+
+    pub fn ranges(&self) -> &[ChromaticRange; VOICE_COUNT] {
+        &self.ranges
+    }
+
+    pub fn weights(&self) -> &VoiceLeadingWeights {
+        &self.weights
+    }
+
+    /// Every voicing of `chord` within range with the bass of the chord in the bass, best first.
+    pub fn voicings(&self, chord: &Chord) -> Vec<Voicing> {
+        self.ranked_voicings(chord)
+            .into_iter()
+            .map(|(_, voicing)| voicing)
+            .collect()
+    }
+
+    /// Rules broken by `voicing` of `chord` on its own.
+    pub fn chord_violations(&self, chord: &Chord, voicing: &Voicing) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        for pair in Voice::ALL.windows(2) {
+            let (upper, lower) = (pair[0], pair[1]);
+            if voicing.note(upper) < voicing.note(lower) {
+                violations.push(Violation::new(
+                    VoiceLeadingRule::VoiceCrossing,
+                    &[lower, upper],
+                ));
+            }
+            let span = (voicing.note(upper) - voicing.note(lower)).semitones();
+            if lower != Voice::Bass && span > CHROMATIC_COUNT as i8 {
+                violations.push(Violation::new(
+                    VoiceLeadingRule::WideSpacing,
+                    &[lower, upper],
+                ));
+            }
+        }
+
+        let present = voicing.pitch_class_set();
+        let fifth = chord
+            .fifth()
+            .filter(|f| f.simple() == PER_5)
+            .map(|f| chord.root().transpose(f).pitch_class());
+        for tone in chord.pitch_class_set().iter() {
+            if !present.contains(tone) {
+                let rule = if Some(tone) == fifth {
+                    VoiceLeadingRule::OmittedFifth
+                } else {
+                    VoiceLeadingRule::MissingChordTone
+                };
+                violations.push(Violation::new(rule, &[]));
+            }
+        }
+        violations
+    }
+
+    /// Rules broken by moving from `from`, voicing `from_chord`, to `to`, voicing `to_chord`.
+    pub fn transition_violations(
+        &self,
+        from_chord: &Chord,
+        from: &Voicing,
+        to_chord: &Chord,
+        to: &Voicing,
+    ) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        let motion = |voice: Voice| (to.note(voice) - from.note(voice)).semitones();
+        let to_set = to_chord.pitch_class_set();
+
+        for &voice in UPPER_VOICES.iter() {
+            let rule = if to_set.contains(from.note(voice).pitch_class()) {
+                Some(VoiceLeadingRule::CommonToneNotRetained).filter(|_| motion(voice) != 0)
+            } else {
+                let nearest = nearest_distance(from.note(voice), to_set);
+                Some(VoiceLeadingRule::NotShortestWay).filter(|_| motion(voice).abs() > nearest)
+            };
+            if let Some(rule) = rule {
+                violations.push(Violation::new(rule, &[voice]));
+            }
+        }
+
+        let bass_direction = motion(Voice::Bass).signum();
+        if (from_chord.pitch_class_set() & to_set).is_empty() && bass_direction != 0 {
+            for &voice in UPPER_VOICES.iter() {
+                if motion(voice).signum() == bass_direction {
+                    violations.push(Violation::new(VoiceLeadingRule::NoContraryMotion, &[voice]));
+                }
+            }
+        }
+
+        for (i, &upper) in Voice::ALL.iter().enumerate() {
+            for &lower in Voice::ALL[i + 1..].iter() {
+                let before = interval_class(from.note(upper), from.note(lower));
+                let after = interval_class(to.note(upper), to.note(lower));
+                let (upper_motion, lower_motion) = (motion(upper), motion(lower));
+                if upper_motion == 0 || lower_motion == 0 || (after != 0 && after != 7) {
+                    continue;
+                }
+                let rule = match (before == after, after) {
+                    (true, 7) => VoiceLeadingRule::ParallelFifths,
+                    (true, _) => VoiceLeadingRule::ParallelOctaves,
+                    (false, _) if upper_motion.signum() != lower_motion.signum() => continue,
+                    (false, 7) => VoiceLeadingRule::HiddenFifths,
+                    (false, _) => VoiceLeadingRule::HiddenOctaves,
+                };
+                violations.push(Violation::new(rule, &[lower, upper]));
+            }
+        }
+
+        for pair in Voice::ALL.windows(2) {
+            let (upper, lower) = (pair[0], pair[1]);
+            if to.note(lower) > from.note(upper) || to.note(upper) < from.note(lower) {
+                violations.push(Violation::new(
+                    VoiceLeadingRule::VoiceCrossing,
+                    &[lower, upper],
+                ));
+            }
+        }
+        violations
+    }
+
+    /// Voices `chords` minimising the weighted cost of all violations and of voice motion, or
+    /// returns `None` if some chord cannot be voiced within the ranges.
+    pub fn lead(&self, chords: &[Chord]) -> Option<VoiceLeading> {
+        let mut candidates = Vec::with_capacity(chords.len());
+        for chord in chords {
+            let mut ranked = self.ranked_voicings(chord);
+            if ranked.is_empty() {
+                return None;
+            }
+            ranked.truncate(CANDIDATE_COUNT);
+            candidates.push(ranked);
+        }
+
+        // NOTE: Viterbi over the candidates, remembering the best predecessor of each
+        let mut costs: Vec<Vec<f64>> = Vec::with_capacity(chords.len());
+        let mut previous: Vec<Vec<usize>> = Vec::with_capacity(chords.len());
+        for (i, chord) in chords.iter().enumerate() {
+            let mut chord_costs = Vec::with_capacity(candidates[i].len());
+            let mut chord_previous = Vec::with_capacity(candidates[i].len());
+            for (static_cost, voicing) in candidates[i].iter() {
+                let (best_ix, best_cost) = match i {
+                    0 => (0, 0.0),
+                    _ => candidates[i - 1]
+                        .iter()
+                        .enumerate()
+                        .map(|(k, (_, from))| {
+                            let cost = costs[i - 1][k]
+                                + self.transition_cost(&chords[i - 1], from, chord, voicing);
+                            (k, cost)
+                        })
+                        .fold(
+                            (0, f64::INFINITY),
+                            |best, x| if x.1 < best.1 { x } else { best },
+                        ),
+                };
+                chord_costs.push(static_cost + best_cost);
+                chord_previous.push(best_ix);
+            }
+            costs.push(chord_costs);
+            previous.push(chord_previous);
+        }
+
+        let last = costs.last()?;
+        let mut ix = (0..last.len()).fold(0, |best, k| if last[k] < last[best] { k } else { best });
+        let cost = last[ix];
+        let mut voicings = vec![candidates[0][0].1; chords.len()];
+        for i in (0..chords.len()).rev() {
+            voicings[i] = candidates[i][ix].1;
+            ix = previous[i][ix];
+        }
+
+        let violations = (0..chords.len())
+            .map(|i| {
+                let mut violations = self.chord_violations(&chords[i], &voicings[i]);
+                if i > 0 {
+                    violations.extend(self.transition_violations(
+                        &chords[i - 1],
+                        &voicings[i - 1],
+                        &chords[i],
+                        &voicings[i],
+                    ));
+                }
+                violations
+            })
+            .collect();
+
+        Some(VoiceLeading {
+            voicings,
+            violations,
+            cost,
+        })
+    }
+
+    fn ranked_voicings(&self, chord: &Chord) -> Vec<(f64, Voicing)> {
+        let chord_set = chord.pitch_class_set();
+        let bass = chord.bass().pitch_class();
+        let options: Vec<Vec<Note>> = Voice::ALL
+            .iter()
+            .map(|&voice| {
+                let range = self.ranges[voice.index()];
+                let lower = usize::from(range.lower_sounding());
+                let upper = usize::from(range.upper_sounding());
+                (lower..=upper)
+                    .map(|n| Note::new(n as i8))
+                    .filter(|n| match voice {
+                        Voice::Bass => n.pitch_class() == bass,
+                        _ => chord_set.contains(n.pitch_class()),
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let mut ranked = Vec::new();
+        for &soprano in options[0].iter() {
+            for &alto in options[1].iter() {
+                for &tenor in options[2].iter() {
+                    for &bass in options[3].iter() {
+                        let voicing = Voicing([soprano, alto, tenor, bass]);
+                        let cost = self.weights.cost(&self.chord_violations(chord, &voicing));
+                        ranked.push((cost, voicing));
+                    }
+                }
+            }
+        }
+        ranked.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        ranked
+    }
+
+    fn transition_cost(
+        &self,
+        from_chord: &Chord,
+        from: &Voicing,
+        to_chord: &Chord,
+        to: &Voicing,
+    ) -> f64 {
+        let motion: i32 = Voice::ALL
+            .iter()
+            .map(|&v| (to.note(v) - from.note(v)).semitones().abs() as i32)
+            .sum();
+        self.weights
+            .cost(&self.transition_violations(from_chord, from, to_chord, to))
+            + self.weights.motion * motion as f64
+    }
+}
+
+/// Four part vocal ranges with the default weights.
+impl Default for VoiceLeader {
+    fn default() -> Self {
+        VoiceLeader::new([SOPRANO, ALTO, TENOR, BASS], VoiceLeadingWeights::default())
+    }
+}
+
+impl VoiceLeading {
+    pub fn voicings(&self) -> &[Voicing] {
+        &self.voicings
+    }
+
+    /// The rules broken by each voicing, and by the move into it from the one before.
+    pub fn violations(&self) -> &[Vec<Violation>] {
+        &self.violations
+    }
+
+    /// The total weighted cost, including voice motion.
+    pub fn cost(&self) -> f64 {
+        self.cost
+    }
+}
+
+/// Semitones between two notes modulo the octave, ignoring which one is higher.
+fn interval_class(a: Note, b: Note) -> i8 {
+    (a - b).semitones().abs() % CHROMATIC_COUNT as i8
+}
+
+/// Semitones from `note` to the nearest note of `set`, up or down.
+fn nearest_distance(note: Note, set: PitchClassSet) -> i8 {
+    set.iter()
+        .map(|pc| {
+            let up = (pc - note.pitch_class()).semitones();
+            up.min(CHROMATIC_COUNT as i8 - up)
+        })
+        .min()
+        .unwrap_or(0)
+}
+
+/******************************************************************************
+* TESTS
+******************************************************************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chord(s: &str) -> Chord {
+        s.parse().unwrap()
+    }
+
+    /// Notes from the soprano down, e.g. `G4 E4 C4 C3`.
+    fn voicing(s: &str) -> Voicing {
+        let notes: Vec<Note> = s.split_whitespace().map(|n| n.parse().unwrap()).collect();
+        Voicing::new([notes[0], notes[1], notes[2], notes[3]])
+    }
+
+    #[test]
+    fn leads_a_cadence() {
+        let chords: Vec<Chord> = ["C", "F", "G", "C"].iter().map(|s| chord(s)).collect();
+        let leading = VoiceLeader::default().lead(&chords).unwrap();
+        let voicings: Vec<String> = leading.voicings().iter().map(Voicing::to_string).collect();
+        assert_eq!(
+            voicings,
+            ["G4 E4 C4 C4", "A4 F4 C4 F3", "G4 D4 B3 G3", "G4 E4 C4 C3"]
+        );
+        assert_eq!(
+            leading.violations(),
+            [
+                vec![],
+                vec![],
+                vec![Violation::new(
+                    VoiceLeadingRule::NotShortestWay,
+                    &[Voice::Alto]
+                )],
+                vec![],
+            ]
+        );
+        assert_eq!(leading.cost(), 31.);
+    }
+
+    #[test]
+    fn voicings_keep_the_bass_of_the_chord() {
+        let leader = VoiceLeader::default();
+        let voicings = leader.voicings(&chord("C/E"));
+        assert!(voicings
+            .iter()
+            .all(|v| v.note(Voice::Bass).to_string().starts_with('E')));
+        assert_eq!(leader.chord_violations(&chord("C/E"), &voicings[0]), []);
+        let narrow = ChromaticRange::new("C3".parse().unwrap(), "D3".parse().unwrap());
+        let leader = VoiceLeader::new([SOPRANO, ALTO, TENOR, narrow], *leader.weights());
+        assert!(leader.lead(&[chord("C"), chord("D")]).is_some());
+        assert_eq!(leader.lead(&[chord("C"), chord("B")]), None);
+    }
+
+    #[test]
+    fn chord_violations() {
+        let leader = VoiceLeader::default();
+        let rules = |chord_symbol: &str, notes: &str| -> Vec<Violation> {
+            leader.chord_violations(&chord(chord_symbol), &voicing(notes))
+        };
+        assert_eq!(rules("C", "C5 G4 E4 C3"), []);
+        assert_eq!(
+            rules("C", "C5 E4 C4 C3"),
+            [Violation::new(VoiceLeadingRule::OmittedFifth, &[])]
+        );
+        assert_eq!(
+            rules("C", "C5 G4 C4 C3"),
+            [Violation::new(VoiceLeadingRule::MissingChordTone, &[])]
+        );
+        assert_eq!(
+            rules("C", "E4 G4 C4 C3"),
+            [Violation::new(
+                VoiceLeadingRule::VoiceCrossing,
+                &[Voice::Alto, Voice::Soprano]
+            )]
+        );
+        assert_eq!(
+            rules("C", "G5 E4 C4 C3"),
+            [Violation::new(
+                VoiceLeadingRule::WideSpacing,
+                &[Voice::Alto, Voice::Soprano]
+            )]
+        );
+    }
+
+    #[test]
+    fn transition_violations() {
+        let leader = VoiceLeader::default();
+        let rules = |from: (&str, &str), to: (&str, &str)| -> Vec<Violation> {
+            leader.transition_violations(
+                &chord(from.0),
+                &voicing(from.1),
+                &chord(to.0),
+                &voicing(to.1),
+            )
+        };
+        assert_eq!(rules(("C", "C5 G4 E4 C3"), ("F", "C5 A4 F4 F2")), []);
+        assert_eq!(
+            rules(("C", "G4 E4 C4 C3"), ("D", "A4 F#4 D4 D3")),
+            [
+                Violation::new(VoiceLeadingRule::NotShortestWay, &[Voice::Soprano]),
+                Violation::new(VoiceLeadingRule::NoContraryMotion, &[Voice::Soprano]),
+                Violation::new(VoiceLeadingRule::NoContraryMotion, &[Voice::Alto]),
+                Violation::new(VoiceLeadingRule::NoContraryMotion, &[Voice::Tenor]),
+                Violation::new(
+                    VoiceLeadingRule::ParallelFifths,
+                    &[Voice::Tenor, Voice::Soprano]
+                ),
+                Violation::new(
+                    VoiceLeadingRule::ParallelFifths,
+                    &[Voice::Bass, Voice::Soprano]
+                ),
+                Violation::new(
+                    VoiceLeadingRule::ParallelOctaves,
+                    &[Voice::Bass, Voice::Tenor]
+                ),
+            ]
+        );
+        assert_eq!(
+            rules(("C", "E5 G4 C4 C3"), ("G", "D5 G4 B3 G2")),
+            [Violation::new(
+                VoiceLeadingRule::HiddenFifths,
+                &[Voice::Bass, Voice::Soprano]
+            )]
+        );
+        assert_eq!(
+            rules(("C", "E5 G4 C4 C3"), ("G", "G5 G4 B3 G3")),
+            [
+                Violation::new(VoiceLeadingRule::NotShortestWay, &[Voice::Soprano]),
+                Violation::new(
+                    VoiceLeadingRule::HiddenOctaves,
+                    &[Voice::Bass, Voice::Soprano]
+                ),
+            ]
+        );
+        assert_eq!(
+            rules(("C", "C5 G4 E4 C3"), ("C", "C5 C5 E4 C3")),
+            [Violation::new(
+                VoiceLeadingRule::CommonToneNotRetained,
+                &[Voice::Alto]
+            )]
+        );
+        assert_eq!(
+            rules(("C", "C5 G4 E4 C3"), ("C/G", "C5 G4 E4 G4")),
+            [Violation::new(
+                VoiceLeadingRule::VoiceCrossing,
+                &[Voice::Bass, Voice::Tenor]
+            )]
+        );
+    }
+}
@@ -0,0 +1,53 @@
+use super::super::{Note, PitchClassSet};
+use super::{Voice, Voicing, VOICE_COUNT};
+
+impl Voice {
+    pub const ALL: [Voice; VOICE_COUNT] = [Voice::Soprano, Voice::Alto, Voice::Tenor, Voice::Bass];
+
+    pub fn index(self) -> usize {
+        self as usize
+    }
+}
+
+impl std::fmt::Display for Voice {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(match self {
+            Voice::Soprano => "soprano",
+            Voice::Alto => "alto",
+            Voice::Tenor => "tenor",
+            Voice::Bass => "bass",
+        })
+    }
+}
+
+impl Voicing {
+    /// `notes` run from the soprano down to the bass.
+    pub fn new(notes: [Note; VOICE_COUNT]) -> Voicing {
+        Voicing(notes)
+    }
+
+    pub fn note(&self, voice: Voice) -> Note {
+        self.0[voice.index()]
+    }
+
+    pub fn notes(&self) -> &[Note; VOICE_COUNT] {
+        &self.0
+    }
+
+    pub fn pitch_class_set(&self) -> PitchClassSet {
+        PitchClassSet::from_notes(&self.0)
+    }
+}
+
+/// Prints the notes from the soprano down, e.g. `C5 G4 E4 C3`.
+impl std::fmt::Display for Voicing {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for (i, note) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{}", note)?;
+        }
+        Ok(())
+    }
+}
@@ -6,12 +6,18 @@ use cpal::traits::{DeviceTrait, EventLoopTrait, HostTrait};
 use cpal::{StreamData, UnknownTypeOutputBuffer};
 
 use muth::note_constants::MIDI_NOTE_COUNT;
-use muth::{BeatDuration, BeatTime, Note, DURATION_MULTIPLIER};
+use muth::{BeatDuration, BeatTime, Note, TempoMap, DURATION_MULTIPLIER};
 
 pub const OK_AUDIO_DELAY_MILLISECONDS: u64 = 5;
 
 pub const TAU: f64 = 2. * std::f64::consts::PI;
 
+pub const MELODY_PATCH: usize = 0;
+/// Soloist 1.
+pub const SINE_PATCH: usize = 1;
+/// Soloist 2.
+pub const TRIANGLE_PATCH: usize = 2;
+
 #[derive(Clone, Copy, Debug, Default)]
 pub struct Voice {
     pub phase: f64,
@@ -36,7 +42,8 @@ pub struct SynthPatch {
 
 #[derive(Clone, Copy, Debug)]
 pub enum SynthCommand {
-    NoteOnForDuration(Note, BeatDuration, f64),
+    /// Plays a note on the patch with the given index, e.g. `MELODY_PATCH`.
+    NoteOnForDuration(usize, Note, BeatDuration, f64),
 }
 
 #[derive(Debug)]
@@ -49,9 +56,10 @@ pub struct Timing {
     pub dt_rel: f64,
     pub t_rel: f64,
     pub beat: f64,
+    pub tempo: TempoMap,
 }
 impl Timing {
-    pub fn new(sample_rate: f64, playback_speed: f64) -> Timing {
+    pub fn new(sample_rate: f64, playback_speed: f64, tempo: TempoMap) -> Timing {
         let dt = 1. / sample_rate;
         Timing {
             sample_rate,
@@ -62,14 +70,17 @@ impl Timing {
             dt_abs: dt,
             t_abs: 0.,
             beat: 0.,
+            tempo,
         }
     }
 
-    pub fn step(&mut self, bpm: f64) {
+    /// Moves on by one sample, reading the beat off the tempo map at the new time rather than
+    /// adding up tempo times sample length, so the beat never drifts.
+    pub fn step(&mut self) {
         self.sample_num += 1;
         self.t_abs = self.sample_num as f64 * self.dt_abs;
         self.t_rel += self.dt_rel;
-        self.beat += (f64::from(DURATION_MULTIPLIER) * self.dt_rel * bpm) / 60.;
+        self.beat = self.tempo.beat_at(self.t_rel);
     }
 }
 
@@ -111,6 +122,7 @@ pub fn wavetable_lerp_sample(wavetable: &[f64; WAVETABLE_SIZE], t: f64) -> f64 {
 
 pub fn run(
     synth_rx: mpsc::Receiver<SynthCommand>,
+    tempo: TempoMap,
 ) -> Result<thread::JoinHandle<()>, Box<dyn Error>> {
     let host = cpal::default_host();
     let event_loop = host.event_loop();
@@ -133,27 +145,52 @@ pub fn run(
         .play_stream(stream_id)
         .expect("failed to play_stream");
 
-    let mut timing = Timing::new(format.sample_rate.0 as f64, 1.);
-    let bpm = 120.; // TODO: ?
+    let mut timing = Timing::new(format.sample_rate.0 as f64, 1., tempo);
 
-    let mut synth_patches = vec![SynthPatch {
-        vib_hz: 4.,
-        vib_amp: 2.,
-        fade_in_t: 1. / 15.,
-        fade_out_t: 1. / 40.,
-        wavetable: wavetable_from_harmonics(vec![
-            1., 0.75, 0.65, 0.55, 0.5, 0.45, 0.4, 0.35, 0.3, 0.25, 0.25, 0.2,
-        ]),
-        voices: [Voice::default(); MIDI_NOTE_COUNT],
-    }];
+    let mut synth_patches = vec![
+        SynthPatch {
+            vib_hz: 4.,
+            vib_amp: 2.,
+            fade_in_t: 1. / 15.,
+            fade_out_t: 1. / 40.,
+            wavetable: wavetable_from_harmonics(vec![
+                1., 0.75, 0.65, 0.55, 0.5, 0.45, 0.4, 0.35, 0.3, 0.25, 0.25, 0.2,
+            ]),
+            voices: [Voice::default(); MIDI_NOTE_COUNT],
+        },
+        SynthPatch {
+            vib_hz: 5.,
+            vib_amp: 1.,
+            fade_in_t: 1. / 40.,
+            fade_out_t: 1. / 40.,
+            wavetable: wavetable_from_harmonics(vec![1.]),
+            voices: [Voice::default(); MIDI_NOTE_COUNT],
+        },
+        SynthPatch {
+            vib_hz: 5.,
+            vib_amp: 1.,
+            fade_in_t: 1. / 40.,
+            fade_out_t: 1. / 40.,
+            wavetable: wavetable_from_harmonics(vec![
+                1.,
+                0.,
+                -1. / 9.,
+                0.,
+                1. / 25.,
+                0.,
+                -1. / 49.,
+                0.,
+                1. / 81.,
+            ]),
+            voices: [Voice::default(); MIDI_NOTE_COUNT],
+        },
+    ];
 
     let mut next_value = move || -> f64 {
-        let synth_patch = &mut synth_patches[0];
-
         match synth_rx.try_recv() {
             Ok(cmd) => match cmd {
-                SynthCommand::NoteOnForDuration(n, beats, velocity) => {
-                    let mut v = &mut synth_patch.voices[usize::from(n)];
+                SynthCommand::NoteOnForDuration(patch, n, beats, velocity) => {
+                    let mut v = &mut synth_patches[patch].voices[usize::from(n)];
                     v.target_freq = n.pitch();
                     v.velocity = velocity;
                     let start_beat = timing.beat.into();
@@ -167,46 +204,54 @@ pub fn run(
             }
         };
 
-        // update voices
-        for voice_ix in 0..MIDI_NOTE_COUNT {
-            let mut v = &mut synth_patch.voices[voice_ix];
-            if v.target_freq == 0. {
-                continue;
-            }
-            if f64::from(v.end_beat) <= timing.beat {
-                continue;
-            }
-            v.freq = v.target_freq
-                + synth_patch.vib_amp * (timing.t_rel * TAU * synth_patch.vib_hz).sin();
-            v.phase = (v.phase + timing.dt_rel * v.freq) % 1.;
-            v.amp = f64::min(
-                f64::min(
-                    lerp(
-                        0.,
-                        1.,
-                        (timing.beat - f64::from(v.start_beat))
-                            / f64::from(DURATION_MULTIPLIER)
-                            / synth_patch.fade_in_t,
-                    ),
-                    lerp(
-                        0.,
-                        1.,
-                        (f64::from(v.end_beat) - timing.beat)
-                            / f64::from(DURATION_MULTIPLIER)
-                            / synth_patch.fade_out_t,
+        for synth_patch in synth_patches.iter_mut() {
+            // update voices
+            for voice_ix in 0..MIDI_NOTE_COUNT {
+                let mut v = &mut synth_patch.voices[voice_ix];
+                if v.target_freq == 0. {
+                    continue;
+                }
+                if f64::from(v.end_beat) <= timing.beat {
+                    continue;
+                }
+                v.freq = v.target_freq
+                    + synth_patch.vib_amp * (timing.t_rel * TAU * synth_patch.vib_hz).sin();
+                v.phase = (v.phase + timing.dt_rel * v.freq) % 1.;
+                v.amp = f64::min(
+                    f64::min(
+                        lerp(
+                            0.,
+                            1.,
+                            (timing.beat - f64::from(v.start_beat))
+                                / f64::from(DURATION_MULTIPLIER)
+                                / synth_patch.fade_in_t,
+                        ),
+                        lerp(
+                            0.,
+                            1.,
+                            (f64::from(v.end_beat) - timing.beat)
+                                / f64::from(DURATION_MULTIPLIER)
+                                / synth_patch.fade_out_t,
+                        ),
                     ),
-                ),
-                1.,
-            ) * wavetable_lerp_sample(&synth_patch.wavetable, v.phase);
+                    1.,
+                ) * v.velocity
+                    * wavetable_lerp_sample(&synth_patch.wavetable, v.phase);
+            }
         }
 
         // sum amplitude
-        let amp =
-            synth_patch.voices.iter().map(|v| v.amp).sum::<f64>() / synth_patch.voices.len() as f64;
-        assert!(amp.abs() <= 1.);
+        let amp = synth_patches
+            .iter()
+            .map(|synth_patch| {
+                synth_patch.voices.iter().map(|v| v.amp).sum::<f64>()
+                    / synth_patch.voices.len() as f64
+            })
+            .sum::<f64>()
+            .clamp(-1., 1.);
 
         // progress time
-        timing.step(bpm);
+        timing.step();
 
         amp * 0.9
     };
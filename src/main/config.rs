@@ -1,7 +1,35 @@
-use muth::Subdivision;
+use muth::{
+    BeatTime, DissonanceCurve, DrumGrid, Form, Species, Subdivision, TempoMap, TimeSignatureMap,
+    IONIAN, SN, WN,
+};
 
 pub struct Config {
-    subdivisions: Vec<Subdivision>,
+    /// Ways of splitting a beat that the melody picks from when filling measures beat by beat.
+    pub subdivisions: Vec<Subdivision>,
+    /// Draws melody notes from a random tone row instead of picking them freely.
+    pub twelve_tone_melody: bool,
+    /// Adds a second voice below the melody, written in this species of counterpoint.
+    pub counterpoint: Option<Species>,
+    /// Mode of the major scale family that chords are drawn from, e.g. `AEOLIAN`.
+    pub mode: usize,
+    /// Measures per phrase of chords, each phrase closing with a cadence.
+    pub phrase_length: usize,
+    /// Phrases before modulating to a related key, or 0 to stay in the starting key.
+    pub phrases_per_key: usize,
+    /// Plays the chord of each measure as a triad below the melody.
+    pub play_chords: bool,
+    /// Target dissonance over time, from 0 to 1, that chords and melody notes lean toward.
+    pub dissonance: DissonanceCurve,
+    /// Sections to play, each generated once and replayed, or varied where primed, after that.
+    /// Sections marked for a solo mute the melody while a soloist improvises over the chords.
+    pub form: Form,
+    /// Time signatures of the measures, notes on strong positions of their meters being played
+    /// harder. A pickup is played on the dominant, leading into the form.
+    pub time_signatures: TimeSignatureMap,
+    /// Tempo over time, shared by the generator and the audio thread.
+    pub tempo: TempoMap,
+    /// Drum patterns written as step sequencer grids, shown in the TUI.
+    pub beats: Vec<DrumGrid>,
 }
 
 impl Default for Config {
@@ -27,6 +55,23 @@ impl Default for Config {
             .iter()
             .map(Subdivision::new)
             .collect(),
+            twelve_tone_melody: false,
+            counterpoint: None,
+            mode: IONIAN,
+            phrase_length: 4,
+            phrases_per_key: 4,
+            play_chords: false,
+            dissonance: DissonanceCurve::constant(0.2).with_point(BeatTime::zero() + WN * 32, 0.5),
+            // The returning section of every other link is a solo, e.g. `B A* B' -> C B C'`.
+            form: (0..24).step_by(2).fold(Form::chain(24, 4), |form, link| {
+                form.with_solo(link * 3 + 1, true)
+            }),
+            time_signatures: TimeSignatureMap::default(),
+            tempo: TempoMap::default(),
+            beats: ["X-x- o-x- X-x- o-xo", "x--x --x- x--x -xo-"]
+                .iter()
+                .map(|grid| DrumGrid::parse(grid, SN).unwrap())
+                .collect(),
         }
     }
 }
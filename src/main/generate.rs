@@ -7,111 +7,532 @@ use rand::{prelude::*, rngs::SmallRng};
 
 use muth::*;
 
-use super::audio::{SynthCommand, Timing, OK_AUDIO_DELAY_MILLISECONDS};
+use super::audio::{
+    SynthCommand, Timing, MELODY_PATCH, OK_AUDIO_DELAY_MILLISECONDS, SINE_PATCH, TRIANGLE_PATCH,
+};
+use super::config::Config;
+
+/// Phrases to choose between, the one closest to the target dissonance winning.
+const PHRASE_CANDIDATES: usize = 8;
+/// How strongly melody notes lean toward the target dissonance.
+const DISSONANCE_BIAS: f64 = 8.;
+/// Semitones up to the tonics of the keys to modulate to.
+const MODULATION_STEPS: [i8; 6] = [7, 5, 2, 3, 4, 8];
+const MODULATION_MODES: [usize; 4] = [IONIAN, DORIAN, MIXOLYDIAN, AEOLIAN];
+/// Chance of each melody note being re-picked in a variation of a section.
+const VARIATION_RATE: f64 = 0.5;
+/// Chance of a new measure developing a remembered motif rather than picking notes afresh.
+const MOTIF_RECALL_CHANCE: f64 = 0.6;
+const MOTIF_MEMORY_CAPACITY: usize = 8;
+/// How much softer notes off the grid of their meter are than notes on the downbeat.
+const ACCENT_DEPTH: f64 = 0.3;
 
 #[derive(Clone, Copy, Debug)]
 struct ChordTrackEvent {
+    tonic: PitchClass,
     mode: Degree,
     degree: Degree,
 }
 
+impl ChordTrackEvent {
+    fn root(self, family: &ScaleFamily) -> PitchClass {
+        self.tonic + family.modes[self.mode.index() as usize][self.degree.index() as usize]
+    }
+
+    /// The mode of the chord's scale starting on its root.
+    fn chord_scale(self, family: &ScaleFamily) -> &Vec<Interval> {
+        family.chord_scale_0(self.mode.index() as usize, self.degree.index() as usize)
+    }
+
+    /// The triad on `degree` of `mode` on `tonic`, rooted in the octave below middle C.
+    fn triad(self, family: &ScaleFamily) -> Vec<Note> {
+        let root = Note::from_pitch_class(self.root(family), 3).unwrap();
+        let chord_scale = self.chord_scale(family);
+        [FIRST, THIRD, FIFTH]
+            .iter()
+            .map(|&i| root + chord_scale[i])
+            .collect()
+    }
+}
+
+/// The material of one measure, kept by the form scheduler for replaying sections.
+#[derive(Clone, Debug)]
+struct Measure {
+    chord: ChordTrackEvent,
+    melody: Line,
+    counterpoint: Option<Line>,
+}
+
+#[derive(Debug, Default)]
+struct ChordTrack {
+    events: Vec<ChordTrackEvent>,
+    beats: Vec<BeatTime>,
+    durations: Vec<BeatDuration>,
+}
+
 #[derive(Debug)]
 struct MelodyTrack {
+    patch: usize,
     notes: Vec<Note>,
     beats: Vec<BeatTime>,
     durations: Vec<BeatDuration>,
 }
 
-pub fn run(synth_tx: mpsc::Sender<SynthCommand>) {
+impl MelodyTrack {
+    fn new(patch: usize) -> MelodyTrack {
+        MelodyTrack {
+            patch,
+            notes: Vec::new(),
+            beats: Vec::new(),
+            durations: Vec::new(),
+        }
+    }
+
+    /// Adds the notes of `line`, shifted to start at `beat`.
+    fn push_line(&mut self, line: &Line, beat: BeatTime) {
+        for i in 0..line.len() {
+            self.notes.push(line.notes()[i]);
+            self.beats
+                .push(beat + (line.onsets()[i] - BeatTime::zero()));
+            self.durations.push(line.durations()[i]);
+        }
+    }
+}
+
+/// Plays through row forms one after the other, picking each form and transposition at random.
+struct SerialMelody {
+    row: ToneRow,
+    pitch_classes: Vec<PitchClass>,
+    ix: usize,
+}
+
+impl SerialMelody {
+    fn new(row: ToneRow) -> SerialMelody {
+        SerialMelody {
+            row,
+            pitch_classes: Vec::new(),
+            ix: 0,
+        }
+    }
+
+    fn next_note(&mut self, rng: &mut SmallRng, octave: i8) -> Note {
+        if self.ix >= self.pitch_classes.len() {
+            let form = *RowForm::ALL.choose(rng).unwrap();
+            let transposition = PitchClass::new(rng.gen_range(0, CHROMATIC_COUNT as i8));
+            self.pitch_classes = self.row.form(form, transposition).to_vec();
+            self.ix = 0;
+        }
+        let pc = self.pitch_classes[self.ix];
+        self.ix += 1;
+        Note::from_pitch_class(pc, octave).unwrap()
+    }
+}
+
+fn pick_melody_note(
+    rng: &mut SmallRng,
+    serial_melody: &mut Option<SerialMelody>,
+    chord: &[Note],
+    target_dissonance: f64,
+    roughness: &Roughness,
+) -> Note {
+    match serial_melody {
+        Some(serial_melody) => serial_melody.next_note(rng, 4),
+        None => {
+            let candidates: Vec<Note> = (60..67).map(Note::new).collect();
+            *candidates
+                .choose_weighted(rng, |&note| {
+                    let dissonance = chord
+                        .iter()
+                        .map(|&tone| roughness.dissonance(&[note, tone]))
+                        .sum::<f64>()
+                        / chord.len().max(1) as f64;
+                    (-DISSONANCE_BIAS * (dissonance - target_dissonance).abs()).exp()
+                })
+                .unwrap()
+        }
+    }
+}
+
+/// The melody note sounding on each of the first `beats` quarter notes of a measure, to serve as a
+/// cantus firmus.
+fn beat_notes(melody: &Line, beats: u64) -> Vec<Note> {
+    (0..beats)
+        .map(|i| {
+            let ix = melody.sounding_at(BeatTime::zero() + QN * i).unwrap_or(0);
+            melody.notes()[ix]
+        })
+        .collect()
+}
+
+/// Fills `melody` up to `end` with notes from `next_note`, up to the next beat first and then beat
+/// by beat, each whole beat split by one of `subdivisions`. The start of the melody lies `offset`
+/// into its bar.
+fn fill_beats<F: FnMut(&mut SmallRng) -> Note>(
+    rng: &mut SmallRng,
+    melody: &mut Line,
+    end: BeatTime,
+    beat: BeatDuration,
+    offset: BeatDuration,
+    subdivisions: &[Subdivision],
+    mut next_note: F,
+) {
+    let start = melody.end();
+    let mut rhythm = Rhythm::default();
+    while start + rhythm.length() < end {
+        let onset = start + rhythm.length();
+        let duration = (beat - (onset + offset) % beat).min(end - onset);
+        let part = Rhythm::new(duration);
+        let part = match subdivisions.choose(rng) {
+            Some(sub) if duration == beat => part.subdivided(sub).unwrap_or(part),
+            _ => part,
+        };
+        rhythm = rhythm.concat(&part);
+    }
+    for (onset, duration) in rhythm.notes() {
+        melody.push(next_note(rng), start + (onset - BeatTime::zero()), duration);
+    }
+}
+
+/// The mean dissonance of the triads of `phrase`.
+fn phrase_dissonance(
+    phrase: &[ChordTrackEvent],
+    family: &ScaleFamily,
+    roughness: &Roughness,
+) -> f64 {
+    let total: f64 = phrase
+        .iter()
+        .map(|event| roughness.dissonance(&event.triad(family)))
+        .sum();
+    total / phrase.len().max(1) as f64
+}
+
+/// A key related to `key`, with the mode it is in.
+fn pick_key(rng: &mut SmallRng, key: &Key, family: &ScaleFamily) -> (Key, usize) {
+    let step = Interval::new(*MODULATION_STEPS.choose(rng).unwrap());
+    let tonic = SpelledPitchClass::common(key.tonic().pitch_class() + step);
+    let mode = *MODULATION_MODES.choose(rng).unwrap();
+    (Key::new(tonic, family, mode), mode)
+}
+
+pub fn run(synth_tx: mpsc::Sender<SynthCommand>, config: Config) {
     // let mut rng = SmallRng::from_entropy();
     let mut rng = SmallRng::seed_from_u64(1337); // TODO: seed from config
 
-    //let mut chord_track_events = Vec::new();
-    //let mut chord_track_beats = Vec::new();
-    //let mut chord_track_durations = Vec::new();
+    let mut serial_melody = if config.twelve_tone_melody {
+        Some(SerialMelody::new(ToneRow::random(&mut rng)))
+    } else {
+        None
+    };
+
+    let family = ScaleFamily::major();
+    let mut mode = config.mode;
+    let mut key = Key::new(SpelledPitchClass::common(PitchClass::new(0)), &family, mode);
+    let mut progression = ProgressionModel::new(&family, mode);
+    let mut phrases_in_key = 0;
+    let roughness = Roughness::default();
+    let mut phrase = Vec::new();
+    let mut phrase_ix = 0;
+    let mut chord_track = ChordTrack::default();
+    let mut motifs = MotifMemory::new(MOTIF_MEMORY_CAPACITY);
 
-    let mut melody_tracks = Vec::new();
-    melody_tracks.push(MelodyTrack {
-        notes: Vec::new(),
-        beats: Vec::new(),
-        durations: Vec::new(),
+    let counterpoint = config.counterpoint.map(|species| {
+        let range = ChromaticRange::new(note_constants::C3, note_constants::B3);
+        CounterpointGenerator::new(species, QN, range)
     });
 
-    let mut gen_measure = |beat: BeatTime, melody_tracks: &mut Vec<MelodyTrack>| {
-        // chord_track_events.push(ChordTrackEvent {
-        //     mode: Degree::new(rng.gen_range(0, 7)),
-        //     degree: Degree::new(rng.gen_range(0, 7)),
-        // });
-        // chord_track_beats.push(beat);
-        // chord_track_durations.push(WN);
-
-        for mt in melody_tracks.iter_mut() {
-            let mut beat = beat;
-            mt.notes.push(Note::new(60 + rng.gen_range(0, 7)));
-            mt.beats.push(beat);
-            mt.durations.push(QN);
-
-            beat += QN;
-            mt.notes.push(Note::new(60 + rng.gen_range(0, 7)));
-            mt.beats.push(beat);
-            mt.durations.push(QN);
-
-            beat += QN;
-            mt.notes.push(Note::new(60 + rng.gen_range(0, 7)));
-            mt.beats.push(beat);
-            mt.durations.push(QN);
-
-            beat += QN;
-            mt.notes.push(Note::new(60 + rng.gen_range(0, 7)));
-            mt.beats.push(beat);
-            mt.durations.push(QN);
+    let mut melody_tracks = vec![MelodyTrack::new(MELODY_PATCH)];
+    if counterpoint.is_some() {
+        melody_tracks.push(MelodyTrack::new(MELODY_PATCH));
+    }
+
+    // Solo sections take turns between the soloists.
+    let improviser = Improviser::new(ChromaticRange::new(note_constants::G4, note_constants::G6));
+    let soloist_ixs = [melody_tracks.len(), melody_tracks.len() + 1];
+    melody_tracks.push(MelodyTrack::new(SINE_PATCH));
+    melody_tracks.push(MelodyTrack::new(TRIANGLE_PATCH));
+    let mut solos = 0;
+    let mut last_solo_note = None;
+
+    let mut scheduler: FormScheduler<Measure> = FormScheduler::new(config.form.clone());
+    let mut bar = 0;
+    let mut gen_measure = |beat: BeatTime,
+                           melody_tracks: &mut Vec<MelodyTrack>,
+                           chord_track: &mut ChordTrack| {
+        let target_dissonance = config.dissonance.at(beat);
+        let signature = config.time_signatures.signature(bar);
+        let length = config.time_signatures.bar_length(bar);
+        let end = BeatTime::zero() + length;
+        let quarters = length / QN;
+
+        if config.time_signatures.is_pickup(bar) {
+            // The pickup leads into the form over the dominant, outside of any section.
+            bar += 1;
+            let chord = ChordTrackEvent {
+                tonic: key.tonic().pitch_class(),
+                mode: Degree::new(mode as i8),
+                degree: Degree::new(4),
+            };
+            chord_track.events.push(chord);
+            chord_track.beats.push(beat);
+            chord_track.durations.push(length);
+            let triad = chord.triad(&family);
+            let mut melody = Line::new();
+            fill_beats(
+                &mut rng,
+                &mut melody,
+                end,
+                signature.beat(),
+                signature.bar() - length,
+                &config.subdivisions,
+                |rng| {
+                    pick_melody_note(
+                        rng,
+                        &mut serial_melody,
+                        &triad,
+                        target_dissonance,
+                        &roughness,
+                    )
+                },
+            );
+            melody_tracks[0].push_line(&melody, beat);
+            return;
         }
-    };
+        bar += 1;
 
-    //let major_family = major_family();
+        let solo = scheduler.is_solo();
+        if solo && scheduler.is_section_start() {
+            solos += 1;
+            last_solo_note = None;
+        }
+        let (_, measure) = scheduler.next(|_, _, source| {
+            if let Some(source) = source {
+                // Variations keep the chords, re-picking some of the melody notes over them.
+                let chord = source.chord;
+                let triad = chord.triad(&family);
+                let mut melody = Line::new();
+                for i in 0..source.melody.len() {
+                    let note = if rng.gen_bool(VARIATION_RATE) {
+                        pick_melody_note(
+                            &mut rng,
+                            &mut serial_melody,
+                            &triad,
+                            target_dissonance,
+                            &roughness,
+                        )
+                    } else {
+                        source.melody.notes()[i]
+                    };
+                    melody.push(
+                        note,
+                        source.melody.onsets()[i],
+                        source.melody.durations()[i],
+                    );
+                }
+                let counterpoint = counterpoint.as_ref().and_then(|counterpoint| {
+                    counterpoint.generate(&beat_notes(&melody, quarters), &key, &mut rng)
+                });
+                return Measure {
+                    chord,
+                    melody,
+                    counterpoint,
+                };
+            }
 
-    let mut timing = Timing::new(1000. / OK_AUDIO_DELAY_MILLISECONDS as f64, 1.);
-    let bpm = 120.;
+            if phrase_ix >= phrase.len() {
+                if config.phrases_per_key > 0 && phrases_in_key >= config.phrases_per_key {
+                    // The modulation makes up a phrase of its own, with the new key taking over
+                    // from there.
+                    let (to, to_mode) = pick_key(&mut rng, &key, &family);
+                    let mut kinds = ModulationKind::ALL;
+                    kinds.shuffle(&mut rng);
+                    let modulation = kinds
+                        .iter()
+                        .find_map(|&kind| Modulation::plan(&key, &to, kind))
+                        .unwrap();
+                    phrase = modulation
+                        .steps()
+                        .iter()
+                        .map(|(step_key, degree)| ChordTrackEvent {
+                            tonic: step_key.tonic().pitch_class(),
+                            mode: Degree::new(if *step_key == to { to_mode } else { mode } as i8),
+                            degree: Degree::new(*degree as i8),
+                        })
+                        .collect();
+                    key = to;
+                    mode = to_mode;
+                    progression = ProgressionModel::new(&family, mode);
+                    phrases_in_key = 0;
+                } else {
+                    let tonic = key.tonic().pitch_class();
+                    let event_mode = Degree::new(mode as i8);
+                    let candidates: Vec<Vec<ChordTrackEvent>> = (0..PHRASE_CANDIDATES)
+                        .map(|_| {
+                            progression
+                                .phrase(config.phrase_length.max(1), &mut rng)
+                                .into_iter()
+                                .map(|degree| ChordTrackEvent {
+                                    tonic,
+                                    mode: event_mode,
+                                    degree,
+                                })
+                                .collect()
+                        })
+                        .collect();
+                    let distance = |phrase: &Vec<ChordTrackEvent>| {
+                        (phrase_dissonance(phrase, &family, &roughness) - target_dissonance).abs()
+                    };
+                    phrase = candidates
+                        .into_iter()
+                        .min_by(|a, b| distance(a).partial_cmp(&distance(b)).unwrap())
+                        .unwrap();
+                    phrases_in_key += 1;
+                }
+                phrase_ix = 0;
+            }
+            let chord = phrase[phrase_ix];
+            phrase_ix += 1;
+
+            let triad = chord.triad(&family);
+            let tonic = chord.tonic;
+            let scale = &family.modes[chord.mode.index() as usize];
+            let mut melody = Line::new();
+            let mut recalled = false;
+            if serial_melody.is_none() && rng.gen_bool(MOTIF_RECALL_CHANCE) {
+                if let Some(motif) = motifs.develop(&mut rng) {
+                    let origin = pick_melody_note(
+                        &mut rng,
+                        &mut serial_melody,
+                        &triad,
+                        target_dissonance,
+                        &roughness,
+                    );
+                    if let Some(line) = motif.line(origin, tonic, scale) {
+                        melody = line.truncated(end);
+                        recalled = true;
+                    }
+                }
+            }
+            // Whatever the motif leaves of the measure is filled up beat by beat.
+            fill_beats(
+                &mut rng,
+                &mut melody,
+                end,
+                signature.beat(),
+                BeatDuration::default(),
+                &config.subdivisions,
+                |rng| {
+                    pick_melody_note(
+                        rng,
+                        &mut serial_melody,
+                        &triad,
+                        target_dissonance,
+                        &roughness,
+                    )
+                },
+            );
+            if !recalled {
+                if let Some(motif) = Motif::from_line(&melody, tonic, scale) {
+                    motifs.remember(motif);
+                }
+            }
+            // The melody of the measure serves as cantus firmus, one note per bar of
+            // counterpoint. Measures where no valid line is found are left silent.
+            let counterpoint = counterpoint.as_ref().and_then(|counterpoint| {
+                counterpoint.generate(&beat_notes(&melody, quarters), &key, &mut rng)
+            });
+            Measure {
+                chord,
+                melody,
+                counterpoint,
+            }
+        });
+
+        chord_track.events.push(measure.chord);
+        chord_track.beats.push(beat);
+        chord_track.durations.push(length);
+
+        if solo {
+            // The melody rests while the soloist improvises over the chord.
+            let line = improviser.with_meter(&signature.meter()).improvise(
+                measure.chord.root(&family),
+                measure.chord.chord_scale(&family),
+                length,
+                last_solo_note,
+                &mut rng,
+            );
+            last_solo_note = line.notes().last().copied().or(last_solo_note);
+            melody_tracks[soloist_ixs[(solos - 1) % soloist_ixs.len()]].push_line(&line, beat);
+        } else {
+            // Measures replayed under a shorter time signature are cut off.
+            melody_tracks[0].push_line(&measure.melody.truncated(end), beat);
+        }
+
+        if let Some(line) = &measure.counterpoint {
+            melody_tracks[1].push_line(&line.truncated(end), beat);
+        }
+    };
+
+    let mut timing = Timing::new(
+        1000. / OK_AUDIO_DELAY_MILLISECONDS as f64,
+        1.,
+        config.tempo.clone(),
+    );
 
     let tick_rate = Duration::from_millis(OK_AUDIO_DELAY_MILLISECONDS);
     let mut last_tick = Instant::now() - tick_rate;
 
-    let mut mel_ix = 0;
+    let mut mel_ixs = vec![0; melody_tracks.len()];
+    let mut chord_ix = 0;
 
     loop {
         if last_tick.elapsed() >= tick_rate {
-            let mut cmd = None;
-
-            let mt = &mut melody_tracks[0];
-            let num_beats = mt.beats.len();
-            if num_beats > 0 {
-                if mel_ix >= num_beats {
-                    let last_ix = num_beats - 1;
-                    gen_measure(
-                        mt.beats[last_ix] + mt.durations[last_ix],
-                        &mut melody_tracks,
-                    );
+            let mut cmds = Vec::new();
+
+            let num_chords = chord_track.beats.len();
+            if num_chords > 0 {
+                if chord_ix >= num_chords {
+                    let last_ix = num_chords - 1;
+                    let beat = chord_track.beats[last_ix] + chord_track.durations[last_ix];
+                    gen_measure(beat, &mut melody_tracks, &mut chord_track);
                 }
             } else {
-                gen_measure(BeatTime::zero(), &mut melody_tracks); // initial case
+                // initial case
+                gen_measure(BeatTime::zero(), &mut melody_tracks, &mut chord_track);
             }
 
-            let mt = &mut melody_tracks[0];
-            if timing.beat >= f64::from(mt.beats[mel_ix]) {
-                cmd = Some(SynthCommand::NoteOnForDuration(
-                    mt.notes[mel_ix],
-                    mt.durations[mel_ix],
-                    1.,
-                ));
-                mel_ix += 1;
+            for (mt, mel_ix) in melody_tracks.iter().zip(mel_ixs.iter_mut()) {
+                if *mel_ix < mt.beats.len() && timing.beat >= f64::from(mt.beats[*mel_ix]) {
+                    let emphasis = config.time_signatures.emphasis(mt.beats[*mel_ix]);
+                    cmds.push(SynthCommand::NoteOnForDuration(
+                        mt.patch,
+                        mt.notes[*mel_ix],
+                        mt.durations[*mel_ix],
+                        1. - ACCENT_DEPTH * (1. - emphasis),
+                    ));
+                    *mel_ix += 1;
+                }
             }
 
-            timing.step(bpm); // TODO: I expect this way of working to go out of sync with the audio thread eventually. How should we handle this?
-
-            if let Some(cmd) = cmd {
-                if synth_tx.send(cmd).is_err() {
-                    break; // NOTE: exiting when disconnected
+            if chord_ix < chord_track.beats.len()
+                && timing.beat >= f64::from(chord_track.beats[chord_ix])
+            {
+                if config.play_chords {
+                    for note in chord_track.events[chord_ix].triad(&family) {
+                        cmds.push(SynthCommand::NoteOnForDuration(
+                            MELODY_PATCH,
+                            note,
+                            chord_track.durations[chord_ix],
+                            0.5,
+                        ));
+                    }
                 }
+                chord_ix += 1;
+            }
+
+            timing.step(); // TODO: I expect this way of working to go out of sync with the audio thread eventually. How should we handle this?
+
+            if cmds.into_iter().any(|cmd| synth_tx.send(cmd).is_err()) {
+                break; // NOTE: exiting when disconnected
             }
             last_tick = Instant::now();
         }
@@ -3,28 +3,26 @@
 ///////////////////////////////////////////////////////////////////////////////////////////////////
 
 mod audio;
-mod ui;
-mod generate;
 mod config;
+mod generate;
+mod ui;
 
-use std::{
-    error::Error,
-    sync::mpsc,
-    thread,
-};
+use std::{error::Error, sync::mpsc, thread};
 
 fn main() -> Result<(), Box<dyn Error>> {
+    let config = config::Config::default();
+    let beats = config.beats.clone();
     let (synth_tx, synth_rx) = mpsc::channel();
 
-    let audio_thread = audio::run(synth_rx)?;
+    let audio_thread = audio::run(synth_rx, config.tempo.clone())?;
 
     let generator_thread = thread::Builder::new()
         .name("generator".into())
         .spawn(move || {
-            generate::run(synth_tx);
+            generate::run(synth_tx, config);
         })?;
 
-    ui::run()?;
+    ui::run(beats)?;
 
     //generator_thread.join().expect("Generator thread panicked");
     drop(generator_thread); // TODO
@@ -19,13 +19,15 @@ pub enum AppCommand {
 pub struct App {
     app_mode: AppMode,
     console: Console,
+    beats: Vec<DrumGrid>,
 }
 
 impl App {
-    pub fn new() -> App {
+    pub fn new(beats: Vec<DrumGrid>) -> App {
         App {
             app_mode: AppMode::Normal,
             console: Console::new(),
+            beats,
         }
     }
 
@@ -46,12 +48,11 @@ impl Widget for App {
             .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
             .split(chunk);
         let mut list_state = tui::widgets::ListState::default();
-        list_state.select(Some(1));
         f.render_stateful_widget(
-            List::new(["Item 1", "Item 2", "Item 3"].iter().map(|i| Text::raw(*i)))
+            List::new(self.beats.iter().map(|beat| Text::raw(beat.to_string())))
                 .block(
                     Block::default()
-                        .title("List")
+                        .title("Beats")
                         .title_style(Style::default().fg(Color::Cyan))
                         .borders(Borders::ALL),
                 )
@@ -1,9 +1,12 @@
 use super::*;
 
+use muth::{parse_chord_symbols, ChordSymbol, ParseChordError};
+
 pub struct Console {
     input_string: String,
     matcher: SkimMatcherV2,
     matched: Option<(usize, i64)>,
+    chords: Option<Result<Vec<ChordSymbol>, ParseChordError>>,
     cmds: Vec<(&'static str, AppCommand)>,
 }
 
@@ -13,6 +16,7 @@ impl Console {
             input_string: "".to_string(),
             matcher: SkimMatcherV2::default().ignore_case(),
             matched: None,
+            chords: None,
             cmds: vec![("quit", AppCommand::QuitApp)],
         }
     }
@@ -28,6 +32,11 @@ impl Console {
             }
         }
         self.matched = matched;
+        // NOTE: anything that is not a command is read as chord symbols, e.g. "Dm7 G7 Cmaj7"
+        self.chords = match matched {
+            None if !input_str.is_empty() => Some(parse_chord_symbols(input_str)),
+            _ => None,
+        };
     }
 }
 
@@ -50,13 +59,26 @@ impl Widget for Console {
                     } else {
                         Text::raw(input_str)
                     },
-                    if let Some((matched_ix, _)) = self.matched {
-                        Text::styled(
+                    match (self.matched, &self.chords) {
+                        (Some((matched_ix, _)), _) => Text::styled(
                             " (".to_string() + self.cmds[matched_ix].0 + ")",
                             Style::default().fg(Color::LightBlue),
-                        )
-                    } else {
-                        Text::raw("")
+                        ),
+                        (None, Some(Ok(chords))) => Text::styled(
+                            format!(
+                                " ({})",
+                                chords
+                                    .iter()
+                                    .map(|c| c.to_string())
+                                    .collect::<Vec<_>>()
+                                    .join(" ")
+                            ),
+                            Style::default().fg(Color::LightBlue),
+                        ),
+                        (None, Some(Err(e))) => {
+                            Text::styled(format!(" ({})", e), Style::default().fg(Color::Red))
+                        }
+                        (None, None) => Text::raw(""),
                     },
                 ]
                 .iter(),
@@ -25,6 +25,8 @@ use crossterm::{
 
 use fuzzy_matcher::{skim::SkimMatcherV2, FuzzyMatcher};
 
+use muth::DrumGrid;
+
 mod app;
 mod console;
 
@@ -44,7 +46,7 @@ pub enum InputEvent {
     Tick,
 }
 
-pub fn run() -> Result<(), Box<dyn Error>> {
+pub fn run(beats: Vec<DrumGrid>) -> Result<(), Box<dyn Error>> {
     let (input_tx, input_rx) = mpsc::channel();
 
     // NOTE: separate thread in order to not block on input
@@ -68,14 +70,17 @@ pub fn run() -> Result<(), Box<dyn Error>> {
             }
         })?;
 
-    run_tui(input_rx).unwrap(); // NOTE: moving rx into here
+    run_tui(input_rx, beats).unwrap(); // NOTE: moving rx into here
 
     input_thread.join().expect("Input thread panicked");
 
     Ok(())
 }
 
-fn run_tui(input_rx: mpsc::Receiver<InputEvent>) -> Result<(), Box<dyn Error>> {
+fn run_tui(
+    input_rx: mpsc::Receiver<InputEvent>,
+    beats: Vec<DrumGrid>,
+) -> Result<(), Box<dyn Error>> {
     enable_raw_mode()?;
     let mut stdout = stdout();
     execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
@@ -84,7 +89,7 @@ fn run_tui(input_rx: mpsc::Receiver<InputEvent>) -> Result<(), Box<dyn Error>> {
     terminal.hide_cursor()?; // NOTE: works natively but not in vscode terminal
     terminal.clear()?;
 
-    let mut app = App::new();
+    let mut app = App::new(beats);
 
     loop {
         terminal.draw(|mut f| {